@@ -1,9 +1,14 @@
 use ark_ec::PairingEngine;
 use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, FromBytes, PrimeField};
 use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
 };
+use hmac::{Hmac, Mac};
 use serde::*;
+use sha2::Sha256;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[derive(Copy, Clone, Debug)]
 pub struct PreparedPublicKey<E: PairingEngine> {
@@ -42,19 +47,52 @@ impl<E: PairingEngine> Default for PublicKey<E> {
     }
 }
 
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    Eq,
-    PartialEq,
-    Serialize,
-    Deserialize,
-    CanonicalSerialize,
-    CanonicalDeserialize,
-)]
+/// Domain separation tag of session keypair derivation
+const KEYPAIR_DERIVATION_DST: &[u8] = b"FERVEO-V01-KEYPAIR-DERIVATION";
+
+/// Minimum length in bytes of the seed session keypairs are derived from
+pub const MIN_SEED_LEN: usize = 32;
+
+/// Identifies a session keypair derived from a seed, see [`Keypair::derive`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DerivationPath {
+    pub chain_id: String,
+    /// The DKG session, or epoch
+    pub tau: u64,
+    pub validator_address: String,
+}
+
+impl DerivationPath {
+    /// Labeled path components, from the root
+    fn components(&self) -> [(&'static [u8], Vec<u8>); 3] {
+        [
+            (b"chain_id", self.chain_id.as_bytes().to_vec()),
+            (b"tau", self.tau.to_be_bytes().to_vec()),
+            (
+                b"validator_address",
+                self.validator_address.as_bytes().to_vec(),
+            ),
+        ]
+    }
+}
+
+/// HMAC-SHA256 of the length-prefixed concatenation of `parts`
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> Zeroizing<[u8; 32]> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(&(part.len() as u64).to_be_bytes());
+        mac.update(part);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().into())
+}
+
+/// The session keypair of a DKG participant
+///
+/// The decryption key is zeroized on drop and redacted from `Debug` output. It is only
+/// serialized through [`Keypair::export_secret_bytes`].
+#[derive(Clone)]
 pub struct Keypair<E: PairingEngine> {
-    #[serde(with = "crate::ark_serde")]
     pub decryption_key: E::Fr,
 }
 
@@ -75,4 +113,159 @@ impl<E: PairingEngine> Keypair<E> {
             decryption_key: E::Fr::rand(rng),
         }
     }
+
+    /// Derives the session keypair of `path` from a master `seed`
+    ///
+    /// Every path component is derived from its parent node as
+    /// node_i = HMAC-SHA256(node_{i-1}, label_i || value_i), starting from
+    /// node_0 = HMAC-SHA256(DST, seed). The decryption key is sampled from the last node by
+    /// rejection sampling, so that it is uniform in `E::Fr`.
+    pub fn derive(seed: &[u8], path: &DerivationPath) -> anyhow::Result<Self> {
+        if seed.len() < MIN_SEED_LEN {
+            anyhow::bail!(
+                "seed must be at least {} bytes long, got {}",
+                MIN_SEED_LEN,
+                seed.len()
+            );
+        }
+        let mut node = hmac_sha256(KEYPAIR_DERIVATION_DST, &[seed]);
+        for (label, value) in path.components() {
+            node = hmac_sha256(node.as_ref(), &[label, &value]);
+        }
+
+        // Candidates are uniform over [0, 2^MODULUS_BITS), accepted if below the modulus
+        let repr_len = <E::Fr as PrimeField>::BigInt::NUM_LIMBS * 8;
+        let modulus_bits = E::Fr::size_in_bits();
+        for counter in 0u32.. {
+            let mut candidate = Zeroizing::new(Vec::with_capacity(repr_len));
+            for block in 0u32.. {
+                if candidate.len() >= repr_len {
+                    break;
+                }
+                candidate.extend_from_slice(
+                    hmac_sha256(
+                        node.as_ref(),
+                        &[
+                            b"candidate",
+                            &counter.to_be_bytes(),
+                            &block.to_be_bytes(),
+                        ],
+                    )
+                    .as_ref(),
+                );
+            }
+            candidate.truncate(repr_len);
+            // Little-endian, so the excess bits are the high bits of the last bytes
+            for (i, byte) in candidate.iter_mut().enumerate() {
+                let bits_below = modulus_bits.saturating_sub(8 * i);
+                if bits_below < 8 {
+                    *byte &= (1u8 << bits_below).wrapping_sub(1);
+                }
+            }
+            let repr = <E::Fr as PrimeField>::BigInt::read(&candidate[..])?;
+            if let Some(decryption_key) = E::Fr::from_repr(repr) {
+                return Ok(Self { decryption_key });
+            }
+        }
+        unreachable!("a candidate is accepted with probability above 1/2")
+    }
+
+    /// Exports the decryption key, in its canonical serialization
+    pub fn export_secret_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![]);
+        self.decryption_key
+            .serialize(&mut *bytes)
+            .expect("serializing to a vector never fails");
+        bytes
+    }
+
+    /// Imports a keypair exported with [`Keypair::export_secret_bytes`]
+    pub fn import_secret_bytes(
+        bytes: &[u8],
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            decryption_key: E::Fr::deserialize(bytes)?,
+        })
+    }
+}
+
+impl<E: PairingEngine> fmt::Debug for Keypair<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("decryption_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<E: PairingEngine> Zeroize for Keypair<E> {
+    fn zeroize(&mut self) {
+        self.decryption_key.zeroize();
+    }
+}
+
+impl<E: PairingEngine> Drop for Keypair<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: PairingEngine> ZeroizeOnDrop for Keypair<E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn test_keypair_export() {
+        let keypair = Keypair::<E>::new(&mut test_rng());
+        let bytes = keypair.export_secret_bytes();
+        let imported = Keypair::<E>::import_secret_bytes(&bytes).unwrap();
+        assert_eq!(imported.public(), keypair.public());
+
+        let debug = format!("{:?}", keypair);
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&format!("{:?}", keypair.decryption_key)));
+    }
+
+    #[test]
+    fn test_keypair_derivation() {
+        let seed = [7u8; MIN_SEED_LEN];
+        let path = DerivationPath {
+            chain_id: "chain".to_string(),
+            tau: 1,
+            validator_address: "validator_0".to_string(),
+        };
+        let keypair = Keypair::<E>::derive(&seed, &path).unwrap();
+        assert_eq!(
+            Keypair::<E>::derive(&seed, &path).unwrap().public(),
+            keypair.public()
+        );
+
+        // Every path component and the seed change the keypair
+        let other_paths = [
+            DerivationPath {
+                chain_id: "other-chain".to_string(),
+                ..path.clone()
+            },
+            DerivationPath {
+                tau: 2,
+                ..path.clone()
+            },
+            DerivationPath {
+                validator_address: "validator_1".to_string(),
+                ..path.clone()
+            },
+        ];
+        for other_path in &other_paths {
+            let other = Keypair::<E>::derive(&seed, other_path).unwrap();
+            assert_ne!(other.public(), keypair.public());
+        }
+        let other = Keypair::<E>::derive(&[8u8; MIN_SEED_LEN], &path).unwrap();
+        assert_ne!(other.public(), keypair.public());
+
+        assert!(Keypair::<E>::derive(&seed[1..], &path).is_err());
+    }
 }
@@ -6,4 +6,5 @@ criterion_main! {
     // benchmarks::pairing::micro,//bench_batch_inverse,
     // benchmarks::pairing::ec,
     benchmarks::validity_checks::validity_checks,
+    benchmarks::pvss::pvss,
 }
@@ -1,3 +1,4 @@
 //pub mod block_proposer;
 // pub mod pairing;
+pub mod pvss;
 pub mod validity_checks;
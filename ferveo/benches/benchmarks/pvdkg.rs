@@ -73,7 +73,7 @@ pub fn setup_dkg(
             shares_num,
         },
         &me,
-        keypairs[validator],
+        keypairs[validator].clone(),
     )
     .expect("Setup failed")
 }
@@ -0,0 +1,52 @@
+use ark_bls12_381::Bls12_381;
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+use digest::crypto_common::rand_core::SeedableRng;
+use ferveo::*;
+use rand::prelude::StdRng;
+
+use super::validity_checks::setup_dkg;
+
+// Run with `--features parallel` to compare against the single-threaded version
+const NUM_SHARES_CASES: [usize; 5] = [4, 8, 16, 32, 64];
+
+fn setup_dealt_dkg(
+    shares_num: u32,
+    rng: &mut StdRng,
+) -> PubliclyVerifiableDkg<Bls12_381> {
+    let mut dkg = setup_dkg(0, shares_num);
+    for i in 0..shares_num {
+        let transcript = setup_dkg(i as usize, shares_num)
+            .share(rng)
+            .expect("Setup failed");
+        let sender = dkg.validators[i as usize].validator.clone();
+        dkg.apply_message(sender, transcript).expect("Setup failed");
+    }
+    dkg
+}
+
+pub fn bench_pvss(c: &mut Criterion) {
+    let mut group = c.benchmark_group("PVSS");
+    group.sample_size(10);
+
+    let rng = &mut StdRng::seed_from_u64(0);
+
+    for shares_num in NUM_SHARES_CASES {
+        let mut dkg = setup_dkg(0, shares_num as u32);
+        let dealt_dkg = setup_dealt_dkg(shares_num as u32, rng);
+
+        group.bench_function(BenchmarkId::new("pvss_deal", shares_num), |b| {
+            b.iter(|| black_box(dkg.share(rng).unwrap()))
+        });
+        let transcript = dealt_dkg.vss.values().next().unwrap();
+        group.bench_function(
+            BenchmarkId::new("pvss_verify_full", shares_num),
+            |b| b.iter(|| black_box(transcript.verify_full(&dealt_dkg))),
+        );
+        group.bench_function(
+            BenchmarkId::new("pvss_aggregate", shares_num),
+            |b| b.iter(|| black_box(aggregate(&dealt_dkg))),
+        );
+    }
+}
+
+criterion_group!(pvss, bench_pvss);
@@ -13,14 +13,14 @@ const NUM_SHARES_CASES: [usize; 5] = [4, 8, 16, 32, 64];
 
 // TODO: Can we expose ferveo test methods to reuse `setup_dkg` et al instead of reimplementing it here?
 
-fn gen_keypairs(num: u32) -> Vec<ferveo_common::Keypair<EllipticCurve>> {
+pub fn gen_keypairs(num: u32) -> Vec<ferveo_common::Keypair<EllipticCurve>> {
     let rng = &mut ark_std::test_rng();
     (0..num)
         .map(|_| ferveo_common::Keypair::<EllipticCurve>::new(rng))
         .collect()
 }
 
-fn gen_validators(
+pub fn gen_validators(
     keypairs: &[ferveo_common::Keypair<EllipticCurve>],
 ) -> Vec<ExternalValidator<EllipticCurve>> {
     (0..keypairs.len())
@@ -31,7 +31,7 @@ fn gen_validators(
         .collect()
 }
 
-fn setup_dkg(
+pub fn setup_dkg(
     validator: usize,
     shares_num: u32,
 ) -> PubliclyVerifiableDkg<EllipticCurve> {
@@ -46,7 +46,7 @@ fn setup_dkg(
             shares_num,
         },
         &me,
-        keypairs[validator],
+        keypairs[validator].clone(),
     )
     .expect("Setup failed")
 }
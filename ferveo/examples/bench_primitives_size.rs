@@ -85,7 +85,7 @@ fn setup_dkg(
             shares_num,
         },
         &me,
-        keypairs[validator],
+        keypairs[validator].clone(),
     )
     .expect("Setup failed")
 }
@@ -47,7 +47,7 @@ pub fn setup_dkg(
             shares_num,
         },
         &me,
-        keypairs[validator],
+        keypairs[validator].clone(),
     )
     .expect("Setup failed")
 }
@@ -16,9 +16,13 @@ use bincode::Options;
 use ed25519_dalek as ed25519;
 
 pub mod common;
+pub mod output;
+pub mod pedersen;
 pub mod pv;
 
 pub use common::*;
+pub use output::*;
+pub use pedersen::*;
 pub use pv::*;
 
 // DKG parameters
@@ -0,0 +1,294 @@
+use crate::*;
+use ark_serialize::*;
+use ferveo_common::Keypair;
+use group_threshold_cryptography::{
+    BlindedKeyShare, PrivateDecryptionContextFast,
+    PrivateDecryptionContextSimple, PrivateKeyShare,
+    PublicDecryptionContextFast, PublicDecryptionContextSimple, PublicKeyShare,
+    SetupParams,
+};
+
+/// The public outcome of a DKG, from which tpke decryption contexts are built
+///
+/// Everything is indexed like the validators of the DKG. With b = dk_i, the aggregated
+/// share Y_i = [dk_i f(ω_i)] H is the blinded key share [b] Z_i of validator i, and its
+/// session public key ek_i = [dk_i] H is the blinding key [b] H.
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+#[serde(bound = "")]
+pub struct DkgOutput<E: PairingEngine> {
+    /// The DKG public key, F_0
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub public_key: E::G1Affine,
+    /// ω_i
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub domain_points: Vec<E::Fr>,
+    /// A_i = [f(ω_i)] G, from the FFT of the aggregated coefficients
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub public_key_shares: Vec<E::G1Affine>,
+    /// Y_i
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub aggregated_shares: Vec<E::G2Affine>,
+    /// ek_i
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub validator_public_keys: Vec<E::G2Affine>,
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub g: E::G1Affine,
+    #[serde(with = "ferveo_common::ark_serde")]
+    pub h: E::G2Affine,
+}
+
+impl<E: PairingEngine> DkgOutput<E> {
+    /// Checks `pvss` is the aggregation of the transcripts of `dkg` and derives the output
+    pub fn new(
+        dkg: &PubliclyVerifiableDkg<E>,
+        pvss: &AggregatedPvss<E>,
+    ) -> Result<Self> {
+        if !matches!(dkg.state, DkgState::Dealt | DkgState::Success { .. }) {
+            return Err(anyhow!("DKG has not dealt enough PVSS transcripts"));
+        }
+        pvss.verify_aggregation(dkg)?;
+        Ok(Self::from_aggregate(
+            &dkg.validators,
+            &dkg.domain,
+            &dkg.pvss_params,
+            &pvss.coeffs,
+            pvss.shares.clone(),
+        ))
+    }
+
+    /// The output of a DKG with the aggregated Feldman commitment `coeffs`, whose
+    /// validators published the blinded key shares `aggregated_shares`
+    pub(crate) fn from_aggregate(
+        validators: &[ferveo_common::Validator<E>],
+        domain: &ark_poly::Radix2EvaluationDomain<E::Fr>,
+        params: &PubliclyVerifiableParams<E>,
+        coeffs: &[E::G1Affine],
+        aggregated_shares: Vec<E::G2Affine>,
+    ) -> Self {
+        let mut commitment = batch_to_projective(coeffs);
+        domain.fft_in_place(&mut commitment);
+        let commitment =
+            E::G1Projective::batch_normalization_into_affine(&commitment);
+
+        Self {
+            public_key: coeffs[0],
+            domain_points: validators
+                .iter()
+                .map(|v| domain.element(v.share_index))
+                .collect(),
+            public_key_shares: validators
+                .iter()
+                .map(|v| commitment[v.share_index])
+                .collect(),
+            aggregated_shares,
+            validator_public_keys: validators
+                .iter()
+                .map(|v| v.validator.public_key.encryption_key)
+                .collect(),
+            g: params.g.into_affine(),
+            h: params.h.into_affine(),
+        }
+    }
+
+    fn blinded_key_share(&self, validator_index: usize) -> BlindedKeyShare<E> {
+        let blinding_key = self.validator_public_keys[validator_index];
+        BlindedKeyShare {
+            blinding_key,
+            blinded_key_share: self.aggregated_shares[validator_index],
+            blinding_key_prepared: E::G2Prepared::from(blinding_key),
+        }
+    }
+
+    fn setup_params(&self, keypair: &Keypair<E>) -> SetupParams<E> {
+        SetupParams {
+            b: keypair.decryption_key,
+            b_inv: keypair.decryption_key.inverse().unwrap(),
+            g: self.g,
+            g_inv: E::G1Prepared::from(-self.g),
+            h_inv: E::G2Prepared::from(-self.h),
+            h: self.h,
+        }
+    }
+
+    /// Checks `keypair` is the session keypair of validator `validator_index`
+    fn check_keypair(
+        &self,
+        validator_index: usize,
+        keypair: &Keypair<E>,
+    ) -> Result<()> {
+        match self.validator_public_keys.get(validator_index) {
+            Some(ek_i) if *ek_i == keypair.public().encryption_key => Ok(()),
+            Some(_) => Err(anyhow!(
+                "keypair is not the session keypair of validator {}",
+                validator_index
+            )),
+            None => Err(anyhow!("unknown validator {}", validator_index)),
+        }
+    }
+
+    pub fn public_decryption_contexts_simple(
+        &self,
+    ) -> Vec<PublicDecryptionContextSimple<E>> {
+        (0..self.domain_points.len())
+            .map(|i| PublicDecryptionContextSimple {
+                domain: self.domain_points[i],
+                public_key_share: PublicKeyShare {
+                    public_key_share: self.public_key_shares[i],
+                },
+                blinded_key_share: self.blinded_key_share(i),
+                h: self.h,
+                validator_public_key: self.validator_public_keys[i]
+                    .into_projective(),
+            })
+            .collect()
+    }
+
+    /// Like [`DkgOutput::public_decryption_contexts_simple`], for the fast variant
+    ///
+    /// The blinded key shares are multiplied by -ω_i^-1, as expected by
+    /// [`group_threshold_cryptography::prepare_combine_fast`].
+    pub fn public_decryption_contexts_fast(
+        &self,
+    ) -> Vec<PublicDecryptionContextFast<E>> {
+        let h_inv = E::G2Prepared::from(-self.h);
+        (0..self.domain_points.len())
+            .map(|i| {
+                let mut blinded_key_share = self.blinded_key_share(i);
+                blinded_key_share.multiply_by_omega_inv(
+                    &self.domain_points[i].inverse().unwrap(),
+                );
+                PublicDecryptionContextFast {
+                    domain: self.domain_points[i],
+                    public_key_share: PublicKeyShare {
+                        public_key_share: self.public_key_shares[i],
+                    },
+                    blinded_key_share,
+                    lagrange_n_0: self.domain_points[i],
+                    h_inv: h_inv.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// The decryption context of validator `validator_index`, from its session `keypair`
+    pub fn private_decryption_context_simple(
+        &self,
+        validator_index: usize,
+        keypair: &Keypair<E>,
+    ) -> Result<PrivateDecryptionContextSimple<E>> {
+        self.check_keypair(validator_index, keypair)?;
+        let setup_params = self.setup_params(keypair);
+        // Z_i = [dk_i^-1] Y_i
+        let private_key_share = PrivateKeyShare {
+            private_key_share: self.aggregated_shares[validator_index]
+                .mul(setup_params.b_inv)
+                .into_affine(),
+        };
+        Ok(PrivateDecryptionContextSimple {
+            index: validator_index,
+            setup_params,
+            private_key_share,
+            public_decryption_contexts: self
+                .public_decryption_contexts_simple(),
+            validator_private_key: keypair.decryption_key,
+        })
+    }
+
+    /// Like [`DkgOutput::private_decryption_context_simple`], for the fast variant
+    ///
+    /// Decryption shares are [dk_i^-1] U.
+    pub fn private_decryption_context_fast(
+        &self,
+        validator_index: usize,
+        keypair: &Keypair<E>,
+    ) -> Result<PrivateDecryptionContextFast<E>> {
+        self.check_keypair(validator_index, keypair)?;
+        let setup_params = self.setup_params(keypair);
+        let private_key_share = PrivateKeyShare {
+            private_key_share: self.aggregated_shares[validator_index]
+                .mul(setup_params.b_inv)
+                .into_affine(),
+        };
+        Ok(PrivateDecryptionContextFast {
+            index: validator_index,
+            setup_params,
+            private_key_share,
+            public_decryption_contexts: self.public_decryption_contexts_fast(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_dkg_output {
+    use super::*;
+    use crate::dkg::pv::test_common::*;
+    use ark_bls12_381::Bls12_381 as E;
+    use ark_std::test_rng;
+    use group_threshold_cryptography as tpke;
+
+    #[test]
+    fn test_dkg_output_simple_decryption() {
+        let rng = &mut test_rng();
+        let dkg = setup_dealt_dkg_with_n_validators(3, 4);
+        let pvss = aggregate(&dkg);
+        let output = DkgOutput::new(&dkg, &pvss).unwrap();
+        assert_eq!(output.public_key, dkg.final_key());
+
+        let mut bytes = vec![];
+        CanonicalSerialize::serialize(&output, &mut bytes).unwrap();
+        let deserialized: DkgOutput<E> =
+            CanonicalDeserialize::deserialize(&bytes[..]).unwrap();
+        assert_eq!(deserialized, output);
+        let bytes = bincode::serialize(&output).unwrap();
+        let deserialized: DkgOutput<E> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, output);
+
+        let keypairs = gen_n_keypairs(4);
+        assert!(output
+            .private_decryption_context_simple(0, &keypairs[1])
+            .is_err());
+        let contexts = keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| {
+                output
+                    .private_decryption_context_simple(i, keypair)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext =
+            tpke::encrypt::<_, E>(msg, aad, &output.public_key, rng);
+        let shares = contexts
+            .iter()
+            .map(|ctx| ctx.create_share(&ciphertext, aad).unwrap())
+            .collect::<Vec<_>>();
+        assert!(tpke::verify_decryption_shares_simple(
+            &contexts[0].public_decryption_contexts,
+            &ciphertext,
+            &shares,
+        ));
+
+        let lagrange = tpke::prepare_combine_simple::<E>(&output.domain_points);
+        let shared_secret =
+            tpke::share_combine_simple::<E>(&shares, &lagrange).unwrap();
+        let plaintext = tpke::checked_decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &contexts[0].setup_params.g_inv,
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext, msg);
+    }
+}
@@ -0,0 +1,751 @@
+use crate::*;
+use anyhow::Context;
+use ark_ec::msm::VariableBaseMSM;
+use ark_poly::Polynomial;
+use ark_serialize::*;
+use ferveo_common::{ExternalValidator, Keypair};
+use group_threshold_cryptography::PrivateKeyShare;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Domain separation of the keys encrypting Pedersen DKG shares
+const PEDERSEN_SHARE_KEY_DST: &[u8] = b"FERVEO-V01-PEDERSEN-DKG-SHARE-KEY";
+
+/// The rounds of a Pedersen DKG, each ended by the caller once its messages are in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PedersenState<E: PairingEngine> {
+    /// Dealers post their dealings
+    Dealing,
+    /// Validators complain against dealers that sent them invalid shares
+    Complaining,
+    /// Dealers answer complaints by revealing the disputed shares
+    Answering,
+    Success {
+        final_key: E::G1Affine,
+    },
+    Invalid,
+}
+
+/// The dealing of one dealer, with a share encrypted point-to-point to every validator
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PedersenDealing<E: PairingEngine> {
+    /// Feldman commitment to the dealt polynomial, F = g^{\phi}
+    pub coeffs: Vec<E::G1Affine>,
+    /// \phi(ω_i), encrypted to ek_i, in the order of the validators
+    pub shares: Vec<EncryptedShare<E>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "")]
+pub enum PedersenMessage<E: PairingEngine> {
+    #[serde(with = "ferveo_common::ark_serde")]
+    Deal(PedersenDealing<E>),
+    /// The dealers whose share to the sender was invalid
+    Complaint(Vec<u32>),
+    /// The shares of the validators that complained against the sender, as
+    /// (validator index, \phi(ω_i))
+    #[serde(with = "ferveo_common::ark_serde")]
+    Answer(Vec<(u32, E::Fr)>),
+}
+
+/// An interactive Joint-Feldman DKG, as in Pedersen's and GJKR's, for small committees
+/// where posting PVSS transcripts is too costly
+///
+/// Every dealer sends each validator its share encrypted to the validator's session key.
+/// A validator complains against dealers whose share does not match the commitment, the
+/// dealers answer by revealing those shares, and dealers that do not answer with valid
+/// shares, or that received at least `security_threshold` complaints, are disqualified.
+/// The result has the same form as the one of [`PubliclyVerifiableDkg`], see
+/// [`PedersenDkg::output`].
+pub struct PedersenDkg<E: PairingEngine> {
+    pub params: Params,
+    pub group_params: PubliclyVerifiableParams<E>,
+    pub session_keypair: Keypair<E>,
+    pub validators: Vec<ferveo_common::Validator<E>>,
+    pub domain: ark_poly::Radix2EvaluationDomain<E::Fr>,
+    pub state: PedersenState<E>,
+    pub me: usize,
+    /// The dealings received, by dealer
+    pub dealings: BTreeMap<u32, PedersenDealing<E>>,
+    /// The validators that complained against each dealer
+    pub complaints: BTreeMap<u32, BTreeSet<u32>>,
+    /// The valid shares each dealer revealed in its answer, by complainer
+    pub answers: BTreeMap<u32, BTreeMap<u32, E::Fr>>,
+    /// The valid shares of `me`, by dealer
+    shares: BTreeMap<u32, E::Fr>,
+    /// The polynomial dealt by `me`, kept to answer complaints
+    polynomial: Option<DensePolynomial<E::Fr>>,
+}
+
+impl<E: PairingEngine> fmt::Debug for PedersenDkg<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PedersenDkg")
+            .field("params", &self.params)
+            .field("group_params", &self.group_params)
+            .field("session_keypair", &self.session_keypair)
+            .field("validators", &self.validators)
+            .field("state", &self.state)
+            .field("me", &self.me)
+            .field("dealings", &self.dealings)
+            .field("complaints", &self.complaints)
+            .field("answers", &self.answers)
+            .field("shares", &"<redacted>")
+            .field("polynomial", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<E: PairingEngine> Drop for PedersenDkg<E> {
+    fn drop(&mut self) {
+        self.shares.values_mut().for_each(|s| s.zeroize());
+        if let Some(polynomial) = self.polynomial.as_mut() {
+            polynomial.coeffs.zeroize();
+        }
+    }
+}
+
+impl<E: PairingEngine> PedersenDkg<E> {
+    /// Create a new DKG context to participate in the DKG
+    /// `validators`: List of validators
+    /// `params` contains the parameters of the DKG such as number of shares
+    /// `me` the validator creating this instance
+    /// `session_keypair` the keypair for `me`
+    pub fn new(
+        validators: Vec<ExternalValidator<E>>,
+        params: Params,
+        me: &ExternalValidator<E>,
+        session_keypair: Keypair<E>,
+    ) -> Result<Self> {
+        let domain = ark_poly::Radix2EvaluationDomain::<E::Fr>::new(
+            params.shares_num as usize,
+        )
+        .ok_or_else(|| anyhow!("unable to construct domain"))?;
+        let me = validators.iter().position(|probe| me == probe).context(
+            "could not find this validator in the provided validator set",
+        )?;
+
+        Ok(Self {
+            params,
+            group_params: PubliclyVerifiableParams::<E> {
+                g: E::G1Projective::prime_subgroup_generator(),
+                h: E::G2Projective::prime_subgroup_generator(),
+            },
+            session_keypair,
+            validators: make_validators(validators),
+            domain,
+            state: PedersenState::Dealing,
+            me,
+            dealings: BTreeMap::new(),
+            complaints: BTreeMap::new(),
+            answers: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            polynomial: None,
+        })
+    }
+
+    /// Create this validator's dealing, to send to every validator
+    pub fn deal<R: Rng>(&mut self, rng: &mut R) -> Result<PedersenMessage<E>> {
+        if self.state != PedersenState::Dealing || self.polynomial.is_some() {
+            return Err(anyhow!("DKG is not in a valid state to deal shares"));
+        }
+        let phi = DensePolynomial::<E::Fr>::rand(
+            (self.params.security_threshold - 1) as usize,
+            rng,
+        );
+        let coeffs =
+            subproductdomain::fast_multiexp(&phi.coeffs, self.group_params.g);
+        let h = self.group_params.h.into_affine();
+        let shares = self
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(i, val)| {
+                let mut share = Zeroizing::new(vec![]);
+                let point = self.domain.element(val.share_index);
+                CanonicalSerialize::serialize(
+                    &phi.evaluate(&point),
+                    &mut *share,
+                )?;
+                EncryptedShare::encrypt(
+                    &share,
+                    &self.associated_data(self.me, i, &coeffs)?,
+                    &val.validator.public_key.encryption_key,
+                    &h,
+                    PEDERSEN_SHARE_KEY_DST,
+                    rng,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.polynomial = Some(phi);
+        Ok(PedersenMessage::Deal(PedersenDealing { coeffs, shares }))
+    }
+
+    /// End the dealing round, complaining against the dealers whose share was invalid
+    pub fn complain(&mut self) -> Result<PedersenMessage<E>> {
+        if self.state != PedersenState::Dealing {
+            return Err(anyhow!("DKG is not in the dealing round"));
+        }
+        self.state = PedersenState::Complaining;
+        Ok(PedersenMessage::Complaint(
+            self.dealings
+                .keys()
+                .filter(|dealer| !self.shares.contains_key(dealer))
+                .copied()
+                .collect(),
+        ))
+    }
+
+    /// End the complaint round, revealing the shares of the validators that complained
+    /// against this dealer
+    pub fn answer(&mut self) -> Result<PedersenMessage<E>> {
+        if self.state != PedersenState::Complaining {
+            return Err(anyhow!("DKG is not in the complaint round"));
+        }
+        self.state = PedersenState::Answering;
+        let answers =
+            match (&self.polynomial, self.complaints.get(&(self.me as u32))) {
+                (Some(phi), Some(complainers)) => complainers
+                    .iter()
+                    .map(|i| {
+                        let point = self
+                            .domain
+                            .element(self.validators[*i as usize].share_index);
+                        (*i, phi.evaluate(&point))
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+        Ok(PedersenMessage::Answer(answers))
+    }
+
+    /// End the answer round, disqualifying dealers and deriving the final key
+    pub fn finalize(&mut self) -> Result<E::G1Affine> {
+        if self.state != PedersenState::Answering {
+            return Err(anyhow!("DKG is not in the answer round"));
+        }
+        let qualified = self.qualified_dealers();
+        let minimum_dealers =
+            self.params.shares_num - self.params.security_threshold;
+        if (qualified.len() as u32) < minimum_dealers
+            || qualified.iter().any(|d| !self.shares.contains_key(d))
+        {
+            self.state = PedersenState::Invalid;
+            return Err(anyhow!("not enough qualified dealers"));
+        }
+        let final_key = self.final_key();
+        self.state = PedersenState::Success { final_key };
+        Ok(final_key)
+    }
+
+    /// The dealers that dealt, received fewer than `security_threshold` complaints and
+    /// answered all of them with valid shares
+    pub fn qualified_dealers(&self) -> Vec<u32> {
+        let no_answers = BTreeMap::new();
+        self.dealings
+            .keys()
+            .filter(|dealer| match self.complaints.get(dealer) {
+                None => true,
+                Some(complainers) => {
+                    let answers =
+                        self.answers.get(dealer).unwrap_or(&no_answers);
+                    complainers.len() < self.params.security_threshold as usize
+                        && complainers.iter().all(|i| answers.contains_key(i))
+                }
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns the public key generated by the DKG
+    pub fn final_key(&self) -> E::G1Affine {
+        self.qualified_dealers()
+            .iter()
+            .map(|d| self.dealings[d].coeffs[0].into_projective())
+            .sum::<E::G1Projective>()
+            .into_affine()
+    }
+
+    /// The private key share Z_i = [x_i] H of this validator, as in tpke
+    pub fn private_key_share(&self) -> Result<PrivateKeyShare<E>> {
+        let mut share = self.share()?;
+        let private_key_share =
+            self.group_params.h.into_affine().mul(share).into_affine();
+        share.zeroize();
+        Ok(PrivateKeyShare { private_key_share })
+    }
+
+    /// The blinded key share [dk_i x_i] H of this validator, which every validator
+    /// publishes once the DKG succeeded to derive the [`DkgOutput`]
+    pub fn blinded_key_share(&self) -> Result<E::G2Affine> {
+        let mut share = self.share()? * self.session_keypair.decryption_key;
+        let blinded_key_share =
+            self.group_params.h.into_affine().mul(share).into_affine();
+        share.zeroize();
+        Ok(blinded_key_share)
+    }
+
+    /// The output of the DKG, from the blinded key shares published by the validators,
+    /// `None` for those that did not publish one yet
+    ///
+    /// Each Y_i is checked against the commitment, e(G, Y_i) = e(A_i, ek_i). The output is
+    /// indexed like the validators of the DKG, so it needs the share of every validator:
+    /// the error names those that are missing.
+    pub fn output(
+        &self,
+        blinded_key_shares: &[Option<E::G2Affine>],
+    ) -> Result<DkgOutput<E>> {
+        if !matches!(self.state, PedersenState::Success { .. }) {
+            return Err(anyhow!("DKG has not succeeded"));
+        }
+        if blinded_key_shares.len() != self.validators.len() {
+            return Err(anyhow!(
+                "expected a blinded key share entry for every validator"
+            ));
+        }
+        let missing = izip!(&self.validators, blinded_key_shares)
+            .filter(|(_, y_i)| y_i.is_none())
+            .map(|(validator, _)| validator.validator.address.as_str())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "missing blinded key shares of validators {}",
+                missing.join(", ")
+            ));
+        }
+        let blinded_key_shares =
+            blinded_key_shares.iter().flatten().copied().collect();
+        let output = DkgOutput::from_aggregate(
+            &self.validators,
+            &self.domain,
+            &self.group_params,
+            &self.aggregated_coeffs(),
+            blinded_key_shares,
+        );
+        let g = self.group_params.g.into_affine();
+        let invalid = izip!(
+            &output.public_key_shares,
+            &output.aggregated_shares,
+            &output.validator_public_keys
+        )
+        .position(|(a_i, y_i, ek_i)| {
+            E::pairing(g, *y_i) != E::pairing(*a_i, *ek_i)
+        });
+        match invalid {
+            Some(i) => Err(anyhow!(
+                "invalid blinded key share of validator {}",
+                self.validators[i].validator.address
+            )),
+            None => Ok(output),
+        }
+    }
+
+    /// Verify a DKG related message
+    /// `sender` is the validator of the sender of the message
+    /// `payload` is the content of the message
+    pub fn verify_message(
+        &self,
+        sender: &ExternalValidator<E>,
+        payload: &PedersenMessage<E>,
+    ) -> Result<()> {
+        let sender = self.sender_index(sender)?;
+        match payload {
+            PedersenMessage::Deal(dealing)
+                if self.state == PedersenState::Dealing =>
+            {
+                if self.dealings.contains_key(&sender) {
+                    Err(anyhow!("Repeat dealer {}", sender))
+                } else if dealing.coeffs.len()
+                    != self.params.security_threshold as usize
+                    || dealing.shares.len() != self.validators.len()
+                {
+                    Err(anyhow!("Malformed dealing"))
+                } else {
+                    Ok(())
+                }
+            }
+            PedersenMessage::Complaint(dealers)
+                if self.state == PedersenState::Complaining =>
+            {
+                match dealers.iter().find(|d| !self.dealings.contains_key(d)) {
+                    Some(d) => Err(anyhow!("Complaint against unknown dealer {}", d)),
+                    None => Ok(()),
+                }
+            }
+            PedersenMessage::Answer(_)
+                if self.state == PedersenState::Answering =>
+            {
+                if self.dealings.contains_key(&sender) {
+                    Ok(())
+                } else {
+                    Err(anyhow!("Answer from unknown dealer {}", sender))
+                }
+            }
+            _ => Err(anyhow!(
+                "DKG state machine is not in correct state to verify this message"
+            )),
+        }
+    }
+
+    /// Apply a message received from `sender` to the state machine
+    pub fn apply_message(
+        &mut self,
+        sender: &ExternalValidator<E>,
+        payload: PedersenMessage<E>,
+    ) -> Result<()> {
+        self.verify_message(sender, &payload)?;
+        let sender = self.sender_index(sender)?;
+        match payload {
+            PedersenMessage::Deal(dealing) => {
+                // Our share is kept only if it matches the commitment, else we complain
+                if let Ok(share) = self.decrypt_share(sender, &dealing) {
+                    self.shares.insert(sender, share);
+                }
+                self.dealings.insert(sender, dealing);
+            }
+            PedersenMessage::Complaint(dealers) => {
+                for dealer in dealers {
+                    self.complaints.entry(dealer).or_default().insert(sender);
+                }
+            }
+            PedersenMessage::Answer(answers) => {
+                let complainers =
+                    self.complaints.get(&sender).cloned().unwrap_or_default();
+                for (i, share) in answers {
+                    if !complainers.contains(&i)
+                        || !self.verify_share(sender, i as usize, &share)
+                    {
+                        continue;
+                    }
+                    if i as usize == self.me {
+                        self.shares.insert(sender, share);
+                    }
+                    self.answers.entry(sender).or_default().insert(i, share);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sender_index(&self, sender: &ExternalValidator<E>) -> Result<u32> {
+        self.validators
+            .iter()
+            .position(|probe| sender == &probe.validator)
+            .map(|i| i as u32)
+            .context("dkg received message from unknown validator")
+    }
+
+    fn decrypt_share(
+        &self,
+        dealer: u32,
+        dealing: &PedersenDealing<E>,
+    ) -> Result<E::Fr> {
+        let plaintext = dealing.shares[self.me].decrypt(
+            &self.associated_data(dealer as usize, self.me, &dealing.coeffs)?,
+            &self.session_keypair.decryption_key,
+            PEDERSEN_SHARE_KEY_DST,
+        )?;
+        let share = E::Fr::deserialize(&plaintext[..])?;
+        if !self.check_share(&dealing.coeffs, self.me, &share) {
+            return Err(anyhow!("share does not match the commitment"));
+        }
+        Ok(share)
+    }
+
+    /// Checks `share` is the share of validator `i` from `dealer`
+    fn verify_share(&self, dealer: u32, i: usize, share: &E::Fr) -> bool {
+        match self.dealings.get(&dealer) {
+            Some(dealing) => self.check_share(&dealing.coeffs, i, share),
+            None => false,
+        }
+    }
+
+    /// [s] G = \sum_k ω_i^k F_k
+    fn check_share(
+        &self,
+        coeffs: &[E::G1Affine],
+        i: usize,
+        share: &E::Fr,
+    ) -> bool {
+        let point = self.domain.element(self.validators[i].share_index);
+        let powers =
+            std::iter::successors(Some(E::Fr::one()), |p| Some(*p * point))
+                .take(coeffs.len())
+                .map(|p| p.into_repr())
+                .collect::<Vec<_>>();
+        VariableBaseMSM::multi_scalar_mul(coeffs, &powers)
+            == self.group_params.g.into_affine().mul(*share)
+    }
+
+    /// x_i = \sum_{d qualified} \phi_d(ω_i)
+    fn share(&self) -> Result<E::Fr> {
+        if !matches!(self.state, PedersenState::Success { .. }) {
+            return Err(anyhow!("DKG has not succeeded"));
+        }
+        Ok(self
+            .qualified_dealers()
+            .iter()
+            .map(|d| self.shares[d])
+            .sum())
+    }
+
+    /// The Feldman commitment to the sum of the qualified dealings
+    fn aggregated_coeffs(&self) -> Vec<E::G1Affine> {
+        let mut coeffs = vec![
+            E::G1Projective::zero();
+            self.params.security_threshold as usize
+        ];
+        for dealer in self.qualified_dealers() {
+            for (a, b) in coeffs.iter_mut().zip(&self.dealings[&dealer].coeffs)
+            {
+                *a += b.into_projective();
+            }
+        }
+        E::G1Projective::batch_normalization_into_affine(&coeffs)
+    }
+
+    /// Binds an encrypted share to (τ, dealer, recipient) and the dealer's commitment
+    fn associated_data(
+        &self,
+        dealer: usize,
+        recipient: usize,
+        coeffs: &[E::G1Affine],
+    ) -> Result<Vec<u8>> {
+        let mut aad = vec![];
+        CanonicalSerialize::serialize(
+            &(self.params.tau, dealer as u64, recipient as u64),
+            &mut aad,
+        )?;
+        CanonicalSerialize::serialize(coeffs, &mut aad)?;
+        Ok(aad)
+    }
+}
+
+#[cfg(test)]
+mod test_pedersen {
+    use super::*;
+    use crate::dkg::pv::test_common::*;
+    use ark_std::test_rng;
+    use group_threshold_cryptography as tpke;
+
+    type E = EllipticCurve;
+    type Fr = <E as PairingEngine>::Fr;
+
+    /// A DKG context for each of the validators of `keypairs`
+    fn setup_pedersen_dkgs(keypairs: &[Keypair<E>]) -> Vec<PedersenDkg<E>> {
+        let validators = gen_validators(keypairs);
+        let params = Params {
+            tau: 0,
+            security_threshold: 2,
+            shares_num: 4,
+        };
+        validators
+            .iter()
+            .zip(keypairs)
+            .map(|(me, keypair)| {
+                PedersenDkg::new(
+                    validators.clone(),
+                    params,
+                    me,
+                    keypair.clone(),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    /// Delivers the message of each validator to every validator
+    fn broadcast(
+        dkgs: &mut [PedersenDkg<E>],
+        validators: &[ExternalValidator<E>],
+        messages: Vec<PedersenMessage<E>>,
+    ) {
+        for (sender, message) in validators.iter().zip(messages) {
+            for dkg in dkgs.iter_mut() {
+                dkg.apply_message(sender, message.clone()).unwrap();
+            }
+        }
+    }
+
+    fn deal(dkgs: &mut [PedersenDkg<E>]) -> Vec<PedersenMessage<E>> {
+        let rng = &mut test_rng();
+        dkgs.iter_mut().map(|dkg| dkg.deal(rng).unwrap()).collect()
+    }
+
+    fn complain(
+        dkgs: &mut [PedersenDkg<E>],
+        validators: &[ExternalValidator<E>],
+    ) -> Vec<PedersenMessage<E>> {
+        let complaints =
+            dkgs.iter_mut().map(|dkg| dkg.complain().unwrap()).collect();
+        broadcast(dkgs, validators, complaints);
+        dkgs.iter_mut().map(|dkg| dkg.answer().unwrap()).collect()
+    }
+
+    fn tamper(message: &mut PedersenMessage<E>, recipient: usize) {
+        match message {
+            PedersenMessage::Deal(dealing) => {
+                dealing.shares[recipient].ciphertext[0] ^= 1
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn check_output(dkgs: &[PedersenDkg<E>], keypairs: &[Keypair<E>]) {
+        let rng = &mut test_rng();
+        let blinded_key_shares = dkgs
+            .iter()
+            .map(|dkg| Some(dkg.blinded_key_share().unwrap()))
+            .collect::<Vec<_>>();
+        let output = dkgs[0].output(&blinded_key_shares).unwrap();
+        assert_eq!(output.public_key, dkgs[0].final_key());
+
+        let contexts = keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| {
+                output
+                    .private_decryption_context_simple(i, keypair)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (dkg, context) in dkgs.iter().zip(&contexts) {
+            assert_eq!(
+                dkg.private_key_share().unwrap(),
+                context.private_key_share
+            );
+        }
+
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext =
+            tpke::encrypt::<_, E>(msg, aad, &output.public_key, rng);
+        let shares = contexts
+            .iter()
+            .take(2)
+            .map(|ctx| ctx.create_share(&ciphertext, aad).unwrap())
+            .collect::<Vec<_>>();
+        let lagrange =
+            tpke::prepare_combine_simple::<E>(&output.domain_points[..2]);
+        let shared_secret =
+            tpke::share_combine_simple::<E>(&shares, &lagrange).unwrap();
+        let plaintext = tpke::checked_decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &contexts[0].setup_params.g_inv,
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext, msg);
+
+        // The output keeps the DKG indices, so every blinded key share is needed
+        let mut partial_key_shares = blinded_key_shares.clone();
+        partial_key_shares[0] = None;
+        partial_key_shares[2] = None;
+        let err = dkgs[0].output(&partial_key_shares).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "missing blinded key shares of validators {}, {}",
+                dkgs[0].validators[0].validator.address,
+                dkgs[0].validators[2].validator.address
+            )
+        );
+
+        // A wrong blinded key share is caught
+        let mut blinded_key_shares = blinded_key_shares;
+        blinded_key_shares.swap(0, 1);
+        assert!(dkgs[0].output(&blinded_key_shares).is_err());
+    }
+
+    /// Test a DKG where every dealer is honest, with the output used for tpke decryption
+    #[test]
+    fn test_pedersen_dkg() {
+        let keypairs = gen_keypairs();
+        let validators = gen_validators(&keypairs);
+        let mut dkgs = setup_pedersen_dkgs(&keypairs);
+        assert!(dkgs[0].answer().is_err());
+        let dealings = deal(&mut dkgs);
+        assert!(dkgs[0].deal(&mut test_rng()).is_err());
+        broadcast(&mut dkgs, &validators, dealings);
+        let answers = complain(&mut dkgs, &validators);
+        assert!(dkgs.iter().all(|dkg| dkg.complaints.is_empty()));
+        broadcast(&mut dkgs, &validators, answers);
+
+        let final_key = dkgs[0].finalize().unwrap();
+        for dkg in dkgs.iter_mut().skip(1) {
+            assert_eq!(dkg.finalize().unwrap(), final_key);
+            assert_eq!(dkg.qualified_dealers(), vec![0, 1, 2, 3]);
+        }
+        assert!(dkgs[0].finalize().is_err());
+        check_output(&dkgs, &keypairs);
+    }
+
+    /// Test a dealer answering a complaint with the valid share stays qualified
+    #[test]
+    fn test_pedersen_dkg_answered_complaint() {
+        let keypairs = gen_keypairs();
+        let validators = gen_validators(&keypairs);
+        let mut dkgs = setup_pedersen_dkgs(&keypairs);
+        let mut dealings = deal(&mut dkgs);
+        tamper(&mut dealings[0], 1);
+        broadcast(&mut dkgs, &validators, dealings);
+        let answers = complain(&mut dkgs, &validators);
+        assert_eq!(dkgs[2].complaints[&0], BTreeSet::from([1]));
+        broadcast(&mut dkgs, &validators, answers);
+
+        for dkg in dkgs.iter_mut() {
+            assert_eq!(dkg.finalize().unwrap(), dkg.final_key());
+            assert_eq!(dkg.qualified_dealers(), vec![0, 1, 2, 3]);
+        }
+        check_output(&dkgs, &keypairs);
+    }
+
+    /// Test dealers with too many complaints, or without valid answers, are disqualified
+    #[test]
+    fn test_pedersen_dkg_disqualification() {
+        let keypairs = gen_keypairs();
+        let validators = gen_validators(&keypairs);
+        let mut dkgs = setup_pedersen_dkgs(&keypairs);
+        let mut dealings = deal(&mut dkgs);
+        // Dealer 0 gets `security_threshold` complaints
+        tamper(&mut dealings[0], 1);
+        tamper(&mut dealings[0], 2);
+        // Dealer 3 answers with a wrong share
+        tamper(&mut dealings[3], 1);
+        let expected_key = [1, 2]
+            .iter()
+            .map(|&i| match &dealings[i] {
+                PedersenMessage::Deal(dealing) => {
+                    dealing.coeffs[0].into_projective()
+                }
+                _ => unreachable!(),
+            })
+            .sum::<<E as PairingEngine>::G1Projective>()
+            .into_affine();
+        broadcast(&mut dkgs, &validators, dealings);
+        let mut answers = complain(&mut dkgs, &validators);
+        answers[3] =
+            PedersenMessage::Answer(vec![(1, Fr::rand(&mut test_rng()))]);
+        broadcast(&mut dkgs, &validators, answers);
+
+        for dkg in dkgs.iter_mut() {
+            assert_eq!(dkg.finalize().unwrap(), expected_key);
+            assert_eq!(dkg.qualified_dealers(), vec![1, 2]);
+        }
+        check_output(&dkgs, &keypairs);
+
+        // Below shares_num - security_threshold qualified dealers the DKG fails
+        let mut dkgs = setup_pedersen_dkgs(&keypairs);
+        let mut dealings = deal(&mut dkgs);
+        for dealing in dealings.iter_mut().take(3) {
+            tamper(dealing, 1);
+            tamper(dealing, 2);
+        }
+        broadcast(&mut dkgs, &validators, dealings);
+        let answers = complain(&mut dkgs, &validators);
+        broadcast(&mut dkgs, &validators, answers);
+        assert!(dkgs[0].finalize().is_err());
+        assert_eq!(dkgs[0].state, PedersenState::Invalid);
+    }
+}
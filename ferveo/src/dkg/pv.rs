@@ -9,19 +9,23 @@ use ferveo_common::{ExternalValidator, PublicKey};
 use std::collections::BTreeMap;
 
 /// The DKG context that holds all of the local state for participating in the DKG
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
-pub struct PubliclyVerifiableDkg<E: PairingEngine> {
+///
+/// Not serializable, since it holds the session keypair, see
+/// [`ferveo_common::Keypair::export_secret_bytes`]. Transcripts are dealt with the
+/// PVSS scheme `S`.
+#[derive(Debug)]
+pub struct PubliclyVerifiableDkg<E: PairingEngine, S: PvssScheme<E> = Pvss<E>> {
     pub params: Params,
     pub pvss_params: PubliclyVerifiableParams<E>,
     pub session_keypair: ferveo_common::Keypair<E>,
     pub validators: Vec<ferveo_common::Validator<E>>,
-    pub vss: BTreeMap<u32, PubliclyVerifiableSS<E>>,
+    pub vss: BTreeMap<u32, S>,
     pub domain: ark_poly::Radix2EvaluationDomain<E::Fr>,
     pub state: DkgState<E>,
     pub me: usize,
 }
 
-impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
+impl<E: PairingEngine, S: PvssScheme<E>> PubliclyVerifiableDkg<E, S> {
     /// Create a new DKG context to participate in the DKG
     /// Every identity in the DKG is linked to an ed25519 public key;
     /// `validators`: List of validators
@@ -84,10 +88,10 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     /// Create a new PVSS instance within this DKG session, contributing to the final key
     /// `rng` is a cryptographic random number generator
     /// Returns a PVSS dealing message to post on-chain
-    pub fn share<R: Rng>(&mut self, rng: &mut R) -> Result<Message<E>> {
+    pub fn share<R: Rng>(&mut self, rng: &mut R) -> Result<Message<E, S>> {
         use ark_std::UniformRand;
         print_time!("PVSS Sharing");
-        let vss = Pvss::<E>::new(&E::Fr::rand(rng), self, rng)?;
+        let vss = S::deal(&E::Fr::rand(rng), self, rng)?;
         match self.state {
             DkgState::Sharing { .. } | DkgState::Dealt => {
                 Ok(Message::Deal(vss))
@@ -99,12 +103,12 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     }
 
     /// Aggregate all received PVSS messages into a single message, prepared to post on-chain
-    pub fn aggregate(&self) -> Result<Message<E>> {
+    pub fn aggregate(&self) -> Result<Message<E, S>> {
         match self.state {
             DkgState::Dealt => {
                 let final_key = self.final_key();
                 Ok(Message::Aggregate(Aggregation {
-                    vss: aggregate(self),
+                    vss: S::aggregate(self)?,
                     final_key,
                 }))
             }
@@ -118,7 +122,7 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     pub fn final_key(&self) -> E::G1Affine {
         self.vss
             .values()
-            .map(|vss| vss.coeffs[0].into_projective())
+            .map(|vss| vss.secret_commitment().into_projective())
             .sum::<E::G1Projective>()
             .into_affine()
     }
@@ -129,7 +133,7 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     pub fn verify_message(
         &self,
         sender: &ExternalValidator<E>,
-        payload: &Message<E>,
+        payload: &Message<E, S>,
     ) -> Result<()> {
         match payload {
             Message::Deal(pvss) if matches!(self.state, DkgState::Sharing{..} | DkgState::Dealt) => {
@@ -150,7 +154,7 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
             Message::Aggregate(Aggregation { vss, final_key }) if matches!(self.state, DkgState::Dealt) => {
                 let minimum_shares = self.params.shares_num
                     - self.params.security_threshold;
-                let verified_shares = vss.verify_aggregation(self)?;
+                let verified_shares = S::verify_aggregation(vss, self)?;
                 // we reject aggregations that fail to meet the security threshold
                 if verified_shares < minimum_shares {
                     Err(
@@ -174,7 +178,7 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     pub fn apply_message(
         &mut self,
         sender: ExternalValidator<E>,
-        payload: Message<E>,
+        payload: Message<E, S>,
     ) -> Result<()> {
         match payload {
             Message::Deal(pvss) if matches!(self.state, DkgState::Sharing{..} | DkgState::Dealt) => {
@@ -213,20 +217,20 @@ impl<E: PairingEngine> PubliclyVerifiableDkg<E> {
     CanonicalDeserialize,
 )]
 #[serde(bound = "")]
-pub struct Aggregation<E: PairingEngine> {
+pub struct Aggregation<E: PairingEngine, S: PvssScheme<E> = Pvss<E>> {
     #[serde(with = "ferveo_common::ark_serde")]
-    vss: AggregatedPvss<E>,
+    vss: S::Aggregated,
     #[serde(with = "ferveo_common::ark_serde")]
     final_key: E::G1Affine,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(bound = "")]
-pub enum Message<E: PairingEngine> {
+pub enum Message<E: PairingEngine, S: PvssScheme<E> = Pvss<E>> {
     #[serde(with = "ferveo_common::ark_serde")]
-    Deal(Pvss<E>),
+    Deal(S),
     #[serde(with = "ferveo_common::ark_serde")]
-    Aggregate(Aggregation<E>),
+    Aggregate(Aggregation<E, S>),
 }
 
 /// Factory functions for testing
@@ -276,6 +280,15 @@ pub(crate) mod test_common {
         shares_num: u32,
         my_index: usize,
     ) -> PubliclyVerifiableDkg<EllipticCurve> {
+        setup_scheme_dkg(security_threshold, shares_num, my_index)
+    }
+
+    /// Like [`setup_dkg_for_n_validators`], dealing with the PVSS scheme `S`
+    pub fn setup_scheme_dkg<S: PvssScheme<EllipticCurve>>(
+        security_threshold: u32,
+        shares_num: u32,
+        my_index: usize,
+    ) -> PubliclyVerifiableDkg<EllipticCurve, S> {
         let keypairs = gen_n_keypairs(shares_num);
         let validators = gen_n_validators(&keypairs, shares_num);
         let me = validators[my_index].clone();
@@ -287,7 +300,7 @@ pub(crate) mod test_common {
                 shares_num,
             },
             &me,
-            keypairs[my_index],
+            keypairs[my_index].clone(),
         )
         .expect("Setup failed")
     }
@@ -310,6 +323,14 @@ pub(crate) mod test_common {
         security_threshold: u32,
         shares_num: u32,
     ) -> PubliclyVerifiableDkg<EllipticCurve> {
+        setup_dealt_scheme_dkg(security_threshold, shares_num)
+    }
+
+    /// Like [`setup_dealt_dkg_with_n_validators`], dealing with the PVSS scheme `S`
+    pub fn setup_dealt_scheme_dkg<S: PvssScheme<EllipticCurve>>(
+        security_threshold: u32,
+        shares_num: u32,
+    ) -> PubliclyVerifiableDkg<EllipticCurve, S> {
         // Make sure that the number of shares is a power of 2 for the FFT to work (Radix-2 FFT domain is being used)
         let is_power_of_2 = |n: u32| n != 0 && (n & (n - 1)) == 0;
         assert!(is_power_of_2(shares_num));
@@ -318,7 +339,7 @@ pub(crate) mod test_common {
 
         // Gather everyone's transcripts
         let transcripts = (0..shares_num).map(|i| {
-            let mut dkg = setup_dkg_for_n_validators(
+            let mut dkg = setup_scheme_dkg::<S>(
                 security_threshold,
                 shares_num,
                 i as usize,
@@ -327,8 +348,7 @@ pub(crate) mod test_common {
         });
 
         // Our test dkg
-        let mut dkg =
-            setup_dkg_for_n_validators(security_threshold, shares_num, 0);
+        let mut dkg = setup_scheme_dkg(security_threshold, shares_num, 0);
         transcripts.enumerate().for_each(|(sender, pvss)| {
             dkg.apply_message(dkg.validators[sender].validator.clone(), pvss)
                 .expect("Setup failed");
@@ -588,7 +608,7 @@ mod test_dealing {
     #[test]
     fn test_pvss_wait_if_not_in_sharing_state() {
         let mut dkg = setup_dkg(0);
-        for state in vec![
+        for state in [
             Dealt,
             DkgState::Success {
                 final_key: G1::zero(),
@@ -0,0 +1,621 @@
+//! Passphrase-encrypted storage of validator secrets at rest
+//!
+//! A secret is stored as an [`EncryptedKey`], a versioned JSON document. The secret is
+//! encrypted with XChaCha20-Poly1305 under a key derived from the passphrase by Argon2id.
+//! Everything but the ciphertext is authenticated as associated data, so that tampering
+//! with the metadata or the KDF parameters fails decryption.
+
+use crate::*;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ferveo_common::Keypair;
+use group_threshold_cryptography::PrivateKeyShare;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Version of the [`EncryptedKey`] format
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// The kind of secret held by an [`EncryptedKey`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    SessionKeypair,
+    PrivateKeyShare,
+}
+
+/// A secret that can be stored in an [`EncryptedKey`]
+pub trait KeystoreSecret: Sized {
+    const KIND: SecretKind;
+
+    fn to_secret_bytes(&self) -> Zeroizing<Vec<u8>>;
+
+    fn from_secret_bytes(bytes: &[u8]) -> Result<Self>;
+
+    /// Whether `public_key`, as stored in [`KeyMetadata`], belongs to this secret
+    fn matches_public_key(&self, public_key: &[u8]) -> bool;
+}
+
+impl<E: PairingEngine> KeystoreSecret for Keypair<E> {
+    const KIND: SecretKind = SecretKind::SessionKeypair;
+
+    fn to_secret_bytes(&self) -> Zeroizing<Vec<u8>> {
+        self.export_secret_bytes()
+    }
+
+    fn from_secret_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Keypair::import_secret_bytes(bytes)?)
+    }
+
+    /// The public key is the serialized [`ferveo_common::PublicKey`]
+    fn matches_public_key(&self, public_key: &[u8]) -> bool {
+        let mut bytes = vec![];
+        CanonicalSerialize::serialize(&self.public(), &mut bytes)
+            .expect("serializing to a vector never fails");
+        bytes == public_key
+    }
+}
+
+impl<E: PairingEngine> KeystoreSecret for PrivateKeyShare<E> {
+    const KIND: SecretKind = SecretKind::PrivateKeyShare;
+
+    fn to_secret_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![]);
+        self.private_key_share
+            .serialize(&mut *bytes)
+            .expect("serializing to a vector never fails");
+        bytes
+    }
+
+    fn from_secret_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(PrivateKeyShare {
+            private_key_share: E::G2Affine::deserialize(bytes)?,
+        })
+    }
+
+    /// The public key is the matching public key share [s_i] G, which is checked
+    /// against the private key share [s_i] H by e([s_i] G, H) = e(G, [s_i] H)
+    fn matches_public_key(&self, public_key: &[u8]) -> bool {
+        match E::G1Affine::deserialize(public_key) {
+            Ok(public_key_share) => {
+                E::pairing(
+                    public_key_share,
+                    E::G2Affine::prime_subgroup_generator(),
+                ) == E::pairing(
+                    E::G1Affine::prime_subgroup_generator(),
+                    self.private_key_share,
+                )
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Public information stored in the clear alongside a secret
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KeyMetadata {
+    /// The DKG session the secret belongs to
+    pub tau: u64,
+    /// The address of the validator owning the secret
+    pub validator_address: String,
+    /// The canonical serialization of the public key matching the secret
+    #[serde(with = "hex_bytes")]
+    pub public_key: Vec<u8>,
+}
+
+impl KeyMetadata {
+    pub fn new<P: CanonicalSerialize>(
+        tau: u64,
+        validator_address: &str,
+        public_key: &P,
+    ) -> Self {
+        let mut public_key_bytes = vec![];
+        public_key
+            .serialize(&mut public_key_bytes)
+            .expect("serializing to a vector never fails");
+        Self {
+            tau,
+            validator_address: validator_address.to_string(),
+            public_key: public_key_bytes,
+        }
+    }
+}
+
+/// Argon2id cost parameters
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory size in KiB
+    pub m_cost: u32,
+    /// Number of iterations
+    pub t_cost: u32,
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: argon2::Params::DEFAULT_M_COST,
+            t_cost: argon2::Params::DEFAULT_T_COST,
+            p_cost: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    // Upper bounds on the parameters read from a keystore file, so that a crafted file
+    // can not make key derivation exhaust memory or time
+
+    /// 1 GiB
+    pub const MAX_M_COST: u32 = 1024 * 1024;
+    pub const MAX_T_COST: u32 = 64;
+    pub const MAX_P_COST: u32 = 16;
+
+    fn check_bounds(&self) -> Result<()> {
+        if self.m_cost > Self::MAX_M_COST
+            || self.t_cost > Self::MAX_T_COST
+            || self.p_cost > Self::MAX_P_COST
+        {
+            return Err(anyhow!("KDF parameters exceed the supported bounds"));
+        }
+        Ok(())
+    }
+
+    fn derive_key(
+        &self,
+        passphrase: &[u8],
+        salt: &[u8],
+    ) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+        let params = argon2::Params::new(
+            self.m_cost,
+            self.t_cost,
+            self.p_cost,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| anyhow!("invalid KDF parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        argon2::Argon2::new(
+            argon2::Algorithm::Argon2id,
+            argon2::Version::V0x13,
+            params,
+        )
+        .hash_password_into(passphrase, salt, &mut *key)
+        .map_err(|e| anyhow!("key derivation failed: {}", e))?;
+        Ok(key)
+    }
+}
+
+/// A secret encrypted under a passphrase, in the keystore file format
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    pub version: u32,
+    pub kind: SecretKind,
+    pub metadata: KeyMetadata,
+    pub kdf: KdfParams,
+    #[serde(with = "hex_bytes")]
+    pub salt: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub nonce: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedKey {
+    /// Encrypts `secret` under `passphrase`, with a fresh salt and nonce
+    pub fn encrypt<S: KeystoreSecret, R: Rng>(
+        secret: &S,
+        metadata: KeyMetadata,
+        passphrase: &[u8],
+        kdf: KdfParams,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let mut salt = vec![0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = vec![0u8; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+        let mut encrypted_key = Self {
+            version: KEYSTORE_VERSION,
+            kind: S::KIND,
+            metadata,
+            kdf,
+            salt,
+            nonce,
+            ciphertext: vec![],
+        };
+
+        let key = kdf.derive_key(passphrase, &encrypted_key.salt)?;
+        let secret_bytes = secret.to_secret_bytes();
+        encrypted_key.ciphertext = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&encrypted_key.nonce),
+                Payload {
+                    msg: &secret_bytes,
+                    aad: &encrypted_key.associated_data(),
+                },
+            )
+            .map_err(|_| anyhow!("encryption failed"))?;
+        Ok(encrypted_key)
+    }
+
+    /// Decrypts the secret with `passphrase`
+    pub fn decrypt<S: KeystoreSecret>(&self, passphrase: &[u8]) -> Result<S> {
+        self.check_format()?;
+        if self.kind != S::KIND {
+            return Err(anyhow!(
+                "keystore holds a {:?}, not a {:?}",
+                self.kind,
+                S::KIND
+            ));
+        }
+        let key = self.kdf.derive_key(passphrase, &self.salt)?;
+        let secret_bytes = Zeroizing::new(
+            XChaCha20Poly1305::new(key.as_ref().into())
+                .decrypt(
+                    XNonce::from_slice(&self.nonce),
+                    Payload {
+                        msg: &self.ciphertext,
+                        aad: &self.associated_data(),
+                    },
+                )
+                .map_err(|_| {
+                    anyhow!("wrong passphrase or corrupted keystore")
+                })?,
+        );
+        let secret = S::from_secret_bytes(&secret_bytes)?;
+        if !secret.matches_public_key(&self.metadata.public_key) {
+            return Err(anyhow!(
+                "keystore secret does not match its public key"
+            ));
+        }
+        Ok(secret)
+    }
+
+    /// Re-encrypts the secret under `new_passphrase`, with a fresh salt and nonce
+    pub fn rotate<S: KeystoreSecret, R: Rng>(
+        &self,
+        passphrase: &[u8],
+        new_passphrase: &[u8],
+        kdf: KdfParams,
+        rng: &mut R,
+    ) -> Result<Self> {
+        let secret = self.decrypt::<S>(passphrase)?;
+        Self::encrypt(&secret, self.metadata.clone(), new_passphrase, kdf, rng)
+    }
+
+    /// Exports the encrypted key as a keystore file
+    pub fn export(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("serializing to JSON never fails")
+    }
+
+    /// Imports an encrypted key from a keystore file
+    pub fn import(keystore: &str) -> Result<Self> {
+        let encrypted_key: Self = serde_json::from_str(keystore)?;
+        encrypted_key.check_format()?;
+        Ok(encrypted_key)
+    }
+
+    fn check_format(&self) -> Result<()> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(anyhow!(
+                "unsupported keystore version {}",
+                self.version
+            ));
+        }
+        if self.salt.len() != SALT_LEN || self.nonce.len() != NONCE_LEN {
+            return Err(anyhow!("malformed keystore"));
+        }
+        self.kdf.check_bounds()
+    }
+
+    /// Everything but the ciphertext
+    fn associated_data(&self) -> Vec<u8> {
+        bincode::serialize(&(
+            self.version,
+            self.kind,
+            &self.metadata,
+            self.kdf,
+            &self.salt,
+            &self.nonce,
+        ))
+        .expect("serializing to a vector never fails")
+    }
+}
+
+/// A storage backend for encrypted keys, addressed by id
+pub trait KeyStore {
+    /// Stores `key` under `id`, replacing any key already stored under it
+    fn store(&mut self, id: &str, key: &EncryptedKey) -> Result<()>;
+
+    fn load(&self, id: &str) -> Result<EncryptedKey>;
+
+    fn delete(&mut self, id: &str) -> Result<()>;
+
+    /// Ids of all stored keys
+    fn ids(&self) -> Result<Vec<String>>;
+}
+
+/// Re-encrypts the key stored under `id` under `new_passphrase`, see [`EncryptedKey::rotate`]
+pub fn rotate_passphrase<S, K, R>(
+    keystore: &mut K,
+    id: &str,
+    passphrase: &[u8],
+    new_passphrase: &[u8],
+    kdf: KdfParams,
+    rng: &mut R,
+) -> Result<()>
+where
+    S: KeystoreSecret,
+    K: KeyStore + ?Sized,
+    R: Rng,
+{
+    let rotated = keystore.load(id)?.rotate::<S, R>(
+        passphrase,
+        new_passphrase,
+        kdf,
+        rng,
+    )?;
+    keystore.store(id, &rotated)
+}
+
+/// Keeps encrypted keys in memory
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryKeyStore {
+    keys: BTreeMap<String, EncryptedKey>,
+}
+
+impl KeyStore for InMemoryKeyStore {
+    fn store(&mut self, id: &str, key: &EncryptedKey) -> Result<()> {
+        self.keys.insert(id.to_string(), key.clone());
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<EncryptedKey> {
+        self.keys
+            .get(id)
+            .cloned()
+            .ok_or_else(|| anyhow!("no key stored under {}", id))
+    }
+
+    fn delete(&mut self, id: &str) -> Result<()> {
+        self.keys
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("no key stored under {}", id))
+    }
+
+    fn ids(&self) -> Result<Vec<String>> {
+        Ok(self.keys.keys().cloned().collect())
+    }
+}
+
+/// Keeps each encrypted key in a `<id>.json` keystore file of a directory
+#[derive(Clone, Debug)]
+pub struct FileKeyStore {
+    dir: PathBuf,
+}
+
+impl FileKeyStore {
+    const EXTENSION: &'static str = "json";
+
+    /// Opens the keystore in `dir`, creating the directory if needed
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        let is_valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_valid {
+            return Err(anyhow!("invalid key id {:?}", id));
+        }
+        Ok(self.dir.join(id).with_extension(Self::EXTENSION))
+    }
+}
+
+impl KeyStore for FileKeyStore {
+    fn store(&mut self, id: &str, key: &EncryptedKey) -> Result<()> {
+        // Write then rename, so that a crash never leaves a truncated keystore file
+        let path = self.path(id)?;
+        let tmp_path = path.with_extension("tmp");
+        // Only the owner may read key files, from the moment they are created
+        if tmp_path.exists() {
+            std::fs::remove_file(&tmp_path)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(key.export().as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<EncryptedKey> {
+        EncryptedKey::import(&std::fs::read_to_string(self.path(id)?)?)
+    }
+
+    fn delete(&mut self, id: &str) -> Result<()> {
+        Ok(std::fs::remove_file(self.path(id)?)?)
+    }
+
+    fn ids(&self) -> Result<Vec<String>> {
+        let mut ids = vec![];
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str())
+                == Some(Self::EXTENSION)
+            {
+                if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+/// Serializes bytes as a hex string
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'d, D: Deserializer<'d>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        use serde::de::Error;
+        let hex_string = String::deserialize(deserializer)?;
+        hex::decode(hex_string).map_err(Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test_keystore {
+    use super::*;
+    use ark_bls12_381::Bls12_381 as E;
+    use ark_std::test_rng;
+
+    /// Cheap parameters, to keep the tests fast
+    const TEST_KDF: KdfParams = KdfParams {
+        m_cost: 256,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let rng = &mut test_rng();
+        let keypair = Keypair::<E>::new(rng);
+        let metadata = KeyMetadata::new(0, "validator_0", &keypair.public());
+
+        let encrypted_key = EncryptedKey::encrypt(
+            &keypair,
+            metadata.clone(),
+            b"passphrase",
+            TEST_KDF,
+            rng,
+        )
+        .unwrap();
+        let imported = EncryptedKey::import(&encrypted_key.export()).unwrap();
+        assert_eq!(imported, encrypted_key);
+        assert_eq!(imported.metadata, metadata);
+
+        let decrypted: Keypair<E> = imported.decrypt(b"passphrase").unwrap();
+        assert_eq!(decrypted.public(), keypair.public());
+
+        assert!(imported.decrypt::<Keypair<E>>(b"wrong").is_err());
+        // The secret is of another kind
+        assert!(imported
+            .decrypt::<PrivateKeyShare<E>>(b"passphrase")
+            .is_err());
+        // The metadata is authenticated
+        let mut tampered = imported.clone();
+        tampered.metadata.tau = 1;
+        assert!(tampered.decrypt::<Keypair<E>>(b"passphrase").is_err());
+        let mut tampered = imported;
+        tampered.version = KEYSTORE_VERSION + 1;
+        assert!(EncryptedKey::import(&tampered.export()).is_err());
+    }
+
+    #[test]
+    fn test_keystore_rejects_untrusted_contents() {
+        let rng = &mut test_rng();
+        let keypair = Keypair::<E>::new(rng);
+
+        // KDF parameters beyond the bounds are rejected before deriving a key
+        let mut encrypted_key = EncryptedKey::encrypt(
+            &keypair,
+            KeyMetadata::new(0, "validator_0", &keypair.public()),
+            b"passphrase",
+            TEST_KDF,
+            rng,
+        )
+        .unwrap();
+        encrypted_key.kdf.m_cost = KdfParams::MAX_M_COST + 1;
+        assert!(EncryptedKey::import(&encrypted_key.export()).is_err());
+        assert!(encrypted_key.decrypt::<Keypair<E>>(b"passphrase").is_err());
+
+        // The secret must match the public key of the metadata
+        let other = Keypair::<E>::new(rng);
+        let mismatched = EncryptedKey::encrypt(
+            &keypair,
+            KeyMetadata::new(0, "validator_0", &other.public()),
+            b"passphrase",
+            TEST_KDF,
+            rng,
+        )
+        .unwrap();
+        assert!(mismatched.decrypt::<Keypair<E>>(b"passphrase").is_err());
+    }
+
+    #[test]
+    fn test_file_keystore_rotation() {
+        let rng = &mut test_rng();
+        let dir = std::env::temp_dir()
+            .join(format!("ferveo-keystore-test-{}", std::process::id()));
+        let mut keystore = FileKeyStore::open(&dir).unwrap();
+
+        let share = PrivateKeyShare::<E> {
+            private_key_share:
+                ark_bls12_381::G2Affine::prime_subgroup_generator(),
+        };
+        let metadata = KeyMetadata::new(
+            0,
+            "validator_0",
+            &ark_bls12_381::G1Affine::prime_subgroup_generator(),
+        );
+        let encrypted_key =
+            EncryptedKey::encrypt(&share, metadata, b"old", TEST_KDF, rng)
+                .unwrap();
+        keystore.store("share", &encrypted_key).unwrap();
+        assert!(keystore.store("../share", &encrypted_key).is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join("share.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(keystore.ids().unwrap(), vec!["share".to_string()]);
+
+        rotate_passphrase::<PrivateKeyShare<E>, _, _>(
+            &mut keystore,
+            "share",
+            b"old",
+            b"new",
+            TEST_KDF,
+            rng,
+        )
+        .unwrap();
+        let rotated = keystore.load("share").unwrap();
+        assert!(rotated.decrypt::<PrivateKeyShare<E>>(b"old").is_err());
+        let decrypted: PrivateKeyShare<E> = rotated.decrypt(b"new").unwrap();
+        assert!(decrypted == share);
+
+        keystore.delete("share").unwrap();
+        assert!(keystore.ids().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
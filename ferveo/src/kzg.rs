@@ -0,0 +1,204 @@
+//! KZG polynomial commitments, with Feist–Khovratovich (FK20) amortized openings
+//!
+//! See https://github.com/khovratovich/Kate/blob/66aae66cd4e99db3182025c27f02e147dfa0c034/Kate_amortized.pdf
+use crate::*;
+use ark_ec::msm::VariableBaseMSM;
+use ark_ff::UniformRand;
+use ark_poly::Polynomial;
+use ark_serialize::*;
+use subproductdomain::toeplitz_mul;
+use zeroize::Zeroize;
+
+/// The structured reference string [τ^i] G, [τ] H of a KZG setup
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgParams<E: PairingEngine> {
+    /// [τ^i] G for i = 0..=max_degree
+    pub powers_of_g: Vec<E::G1Affine>,
+    pub h: E::G2Affine,
+    /// [τ] H
+    pub beta_h: E::G2Affine,
+}
+
+impl<E: PairingEngine> KzgParams<E> {
+    /// A setup for polynomials of degree up to `max_degree`, from a fresh τ
+    ///
+    /// Whoever runs this learns τ and can forge openings, so outside of tests the
+    /// parameters should come from a ceremony.
+    pub fn setup<R: Rng>(max_degree: usize, rng: &mut R) -> Self {
+        let mut tau = E::Fr::rand(rng);
+        let mut powers = Vec::with_capacity(max_degree + 1);
+        let mut power = E::Fr::one();
+        for _ in 0..=max_degree {
+            powers.push(power);
+            power *= tau;
+        }
+        let powers_of_g = subproductdomain::fast_multiexp(
+            &powers,
+            E::G1Projective::prime_subgroup_generator(),
+        );
+        let h = E::G2Affine::prime_subgroup_generator();
+        let beta_h = h.mul(tau).into_affine();
+        tau.zeroize();
+        powers.zeroize();
+        Self {
+            powers_of_g,
+            h,
+            beta_h,
+        }
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    pub fn g(&self) -> E::G1Affine {
+        self.powers_of_g[0]
+    }
+
+    /// [f(τ)] G
+    pub fn commit(&self, f: &DensePolynomial<E::Fr>) -> Result<E::G1Affine> {
+        if f.coeffs.len() > self.powers_of_g.len() {
+            return Err(anyhow!(
+                "polynomial of degree {} is above the maximum degree {}",
+                f.degree(),
+                self.max_degree()
+            ));
+        }
+        let scalars = cfg_iter!(f.coeffs)
+            .map(|c| c.into_repr())
+            .collect::<Vec<_>>();
+        Ok(VariableBaseMSM::multi_scalar_mul(
+            &self.powers_of_g[..scalars.len()],
+            &scalars,
+        )
+        .into_affine())
+    }
+
+    /// Opens f at `point`, returning f(point) and the proof [q(τ)] G for
+    /// q = (f - f(point)) / (X - point)
+    pub fn open(
+        &self,
+        f: &DensePolynomial<E::Fr>,
+        point: &E::Fr,
+    ) -> Result<(E::Fr, E::G1Affine)> {
+        // Synthetic division by (X - point), whose remainder is f(point)
+        let mut quotient =
+            vec![E::Fr::zero(); f.coeffs.len().saturating_sub(1)];
+        let mut remainder =
+            f.coeffs.last().copied().unwrap_or_else(E::Fr::zero);
+        for k in (0..quotient.len()).rev() {
+            quotient[k] = remainder;
+            remainder = f.coeffs[k] + *point * remainder;
+        }
+        let proof =
+            self.commit(&DensePolynomial::from_coefficients_vec(quotient))?;
+        Ok((remainder, proof))
+    }
+
+    /// The opening proofs of f at every element of `domain`, in O(n log n) group operations
+    ///
+    /// FK20: the proof at ω^i is \sum_j h_j ω^{i(j-1)} for h_j = \sum_{l >= j} f_l [τ^{l-j}] G,
+    /// so the h_j are a Toeplitz matrix-vector product and the proofs their FFT.
+    pub fn open_all<D: EvaluationDomain<E::Fr>>(
+        &self,
+        f: &DensePolynomial<E::Fr>,
+        domain: &D,
+    ) -> Result<Vec<E::G1Affine>> {
+        let degree = f.degree();
+        if f.coeffs.len() > self.powers_of_g.len() {
+            return Err(anyhow!(
+                "polynomial of degree {} is above the maximum degree {}",
+                degree,
+                self.max_degree()
+            ));
+        }
+        if degree > domain.size() {
+            return Err(anyhow!(
+                "polynomial of degree {} can not be opened over a domain of size {}",
+                degree,
+                domain.size()
+            ));
+        }
+        if degree == 0 {
+            // Constant polynomials open with the identity everywhere
+            return Ok(vec![E::G1Affine::zero(); domain.size()]);
+        }
+
+        // h_1, ..., h_degree, padded with zeros
+        let (mut h, _) = toeplitz_mul::<E, false>(
+            f,
+            &self.powers_of_g[..degree],
+            degree + 1,
+        )?;
+        h.resize(domain.size(), E::G1Projective::zero());
+        domain.fft_in_place(&mut h);
+        Ok(E::G1Projective::batch_normalization_into_affine(&h))
+    }
+
+    /// Checks that `proof` opens `commitment` to `evaluation` at `point`:
+    /// e(C - [y] G, H) = e(π, [τ] H - [point] H)
+    pub fn verify(
+        &self,
+        commitment: &E::G1Affine,
+        point: &E::Fr,
+        evaluation: &E::Fr,
+        proof: &E::G1Affine,
+    ) -> bool {
+        let lhs = commitment.into_projective() - self.g().mul(*evaluation);
+        let rhs = self.beta_h.into_projective() - self.h.mul(*point);
+        E::product_of_pairings(&[
+            (lhs.into_affine().into(), self.h.into()),
+            ((-*proof).into(), rhs.into_affine().into()),
+        ]) == E::Fqk::one()
+    }
+}
+
+#[cfg(test)]
+mod test_kzg {
+    use super::*;
+    use ark_bls12_381::Bls12_381 as E;
+    use ark_std::test_rng;
+
+    type Fr = <E as PairingEngine>::Fr;
+
+    #[test]
+    fn test_kzg_open_and_verify() {
+        let rng = &mut test_rng();
+        let params = KzgParams::<E>::setup(16, rng);
+        let f = DensePolynomial::<Fr>::rand(16, rng);
+        let commitment = params.commit(&f).unwrap();
+
+        let point = Fr::rand(rng);
+        let (evaluation, proof) = params.open(&f, &point).unwrap();
+        assert_eq!(evaluation, f.evaluate(&point));
+        assert!(params.verify(&commitment, &point, &evaluation, &proof));
+        assert!(!params.verify(
+            &commitment,
+            &point,
+            &(evaluation + Fr::one()),
+            &proof
+        ));
+
+        let too_large = DensePolynomial::<Fr>::rand(17, rng);
+        assert!(params.commit(&too_large).is_err());
+    }
+
+    #[test]
+    fn test_kzg_open_all_matches_single_openings() {
+        let rng = &mut test_rng();
+        let params = KzgParams::<E>::setup(16, rng);
+        let domain = ark_poly::Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        for degree in [0, 1, 7, 8, 15, 16] {
+            let f = DensePolynomial::<Fr>::rand(degree, rng);
+            let commitment = params.commit(&f).unwrap();
+            let proofs = params.open_all(&f, &domain).unwrap();
+            assert_eq!(proofs.len(), domain.size());
+            for (i, proof) in proofs.iter().enumerate() {
+                let point = domain.element(i);
+                let (evaluation, expected) = params.open(&f, &point).unwrap();
+                assert_eq!(*proof, expected);
+                assert!(params.verify(&commitment, &point, &evaluation, proof));
+            }
+        }
+    }
+}
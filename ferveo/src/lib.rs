@@ -1,6 +1,8 @@
 #![allow(unused_imports)]
 
 pub mod dkg;
+pub mod keystore;
+pub mod kzg;
 pub mod vss;
 
 pub mod primitives;
@@ -18,11 +20,15 @@ use ark_poly::{
     polynomial::univariate::DensePolynomial, polynomial::UVPolynomial,
     EvaluationDomain,
 };
-use ark_std::{end_timer, start_timer};
+use ark_std::{cfg_iter, cfg_iter_mut, end_timer, start_timer};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::*;
 
 use anyhow::{anyhow, Result};
 pub use dkg::*;
+pub use keystore::*;
+pub use kzg::*;
 pub use vss::*;
 
 use ark_ec::msm::FixedBaseMSM;
@@ -70,37 +76,31 @@ mod test_dkg_full {
             });
 
         let pvss_aggregated = aggregate(dkg);
+        let dkg_output = DkgOutput::new(dkg, &pvss_aggregated).unwrap();
 
         let decryption_shares: Vec<DecryptionShareSimple<E>> =
             validator_keypairs
                 .iter()
                 .enumerate()
                 .map(|(validator_index, validator_keypair)| {
-                    pvss_aggregated.make_decryption_share_simple(
-                        ciphertext,
-                        aad,
-                        &validator_keypair.decryption_key,
-                        validator_index,
-                        &dkg.pvss_params.g_inv(),
-                    )
+                    dkg_output
+                        .private_decryption_context_simple(
+                            validator_index,
+                            validator_keypair,
+                        )
+                        .unwrap()
+                        .create_share(ciphertext, aad)
+                        .unwrap()
                 })
                 .collect();
 
-        let domain = &dkg
-            .domain
-            .elements()
-            .take(decryption_shares.len())
-            .collect::<Vec<_>>();
-        assert_eq!(domain.len(), decryption_shares.len());
-
-        // TODO: Consider refactor this part into tpke::combine_simple and expose it
-        //  as a public API in tpke::api
-
-        let lagrange_coeffs = tpke::prepare_combine_simple::<E>(domain);
+        let lagrange_coeffs =
+            tpke::prepare_combine_simple::<E>(&dkg_output.domain_points);
         let shared_secret = tpke::share_combine_simple::<E>(
             &decryption_shares,
             &lagrange_coeffs,
-        );
+        )
+        .unwrap();
 
         (pvss_aggregated, decryption_shares, shared_secret)
     }
@@ -161,6 +161,147 @@ mod test_dkg_full {
         assert_eq!(plaintext, msg);
     }
 
+    #[test]
+    fn test_dkg_fast_decryption_variant() {
+        let rng = &mut test_rng();
+
+        let dkg = setup_dealt_dkg_with_n_validators(3, 4);
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let public_key = dkg.final_key();
+        let ciphertext = tpke::encrypt::<_, E>(msg, aad, &public_key, rng);
+        let validator_keypairs = gen_n_keypairs(4);
+
+        let pvss_aggregated = aggregate(&dkg);
+        let dkg_output = DkgOutput::new(&dkg, &pvss_aggregated).unwrap();
+        let public_contexts = dkg_output.public_decryption_contexts_fast();
+        let g_inv = dkg.pvss_params.g_inv();
+
+        // Any subset of security_threshold validators decrypts
+        let decryption_shares = validator_keypairs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(validator_index, validator_keypair)| {
+                pvss_aggregated
+                    .make_decryption_share_fast(
+                        &ciphertext,
+                        aad,
+                        &validator_keypair.decryption_key,
+                        validator_index,
+                        &g_inv,
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(tpke::verify_decryption_shares_fast(
+            &public_contexts,
+            &ciphertext,
+            &decryption_shares,
+        ));
+        assert!(pvss_aggregated
+            .make_decryption_share_fast(
+                &ciphertext,
+                "bad aad".as_bytes(),
+                &validator_keypairs[0].decryption_key,
+                0,
+                &g_inv,
+            )
+            .is_err());
+
+        let prepared_key_shares =
+            tpke::prepare_combine_fast(&public_contexts, &decryption_shares);
+        let shared_secret =
+            tpke::share_combine_fast(&decryption_shares, &prepared_key_shares);
+        let plaintext = tpke::checked_decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &g_inv,
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext, msg);
+
+        // Shares of the private contexts match those of the aggregated transcript
+        let context = dkg_output
+            .private_decryption_context_fast(1, &validator_keypairs[1])
+            .unwrap();
+        assert_eq!(
+            context
+                .create_share(&ciphertext, aad, &g_inv)
+                .unwrap()
+                .decryption_share,
+            decryption_shares[0].decryption_share
+        );
+    }
+
+    #[test]
+    fn test_dkg_simple_decryption_variant_precomputed() {
+        let rng = &mut test_rng();
+
+        let dkg = setup_dealt_dkg_with_n_validators(3, 4);
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let public_key = dkg.final_key();
+        let ciphertext = tpke::encrypt::<_, E>(msg, aad, &public_key, rng);
+        let validator_keypairs = gen_n_keypairs(4);
+
+        let pvss_aggregated = aggregate(&dkg);
+        let dkg_output = DkgOutput::new(&dkg, &pvss_aggregated).unwrap();
+        let public_contexts = dkg_output.public_decryption_contexts_simple();
+
+        let committee = vec![0, 2, 3];
+        let decryption_shares = committee
+            .iter()
+            .map(|validator_index| {
+                pvss_aggregated
+                    .make_decryption_share_simple_precomputed(
+                        &ciphertext,
+                        aad,
+                        &validator_keypairs[*validator_index].decryption_key,
+                        *validator_index,
+                        &committee,
+                        &dkg,
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(pvss_aggregated
+            .make_decryption_share_simple_precomputed(
+                &ciphertext,
+                aad,
+                &validator_keypairs[1].decryption_key,
+                1,
+                &committee,
+                &dkg,
+            )
+            .is_err());
+
+        let shared_secret = tpke::checked_share_combine_simple_precomputed(
+            &public_contexts,
+            &committee,
+            &ciphertext,
+            &decryption_shares,
+        )
+        .unwrap();
+        let plaintext = tpke::checked_decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &dkg.pvss_params.g_inv(),
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext, msg);
+
+        assert!(tpke::checked_share_combine_simple_precomputed(
+            &public_contexts,
+            &[0, 1, 3],
+            &ciphertext,
+            &decryption_shares,
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_dkg_simple_decryption_shares_verification() {
         let rng = &mut test_rng();
@@ -329,9 +470,12 @@ mod test_dkg_full {
             .unwrap(),
         );
 
+        // The recovered share is at the random point
+        domain_points.push(x_r);
         let lagrange = tpke::prepare_combine_simple::<E>(&domain_points);
         let new_shared_secret =
-            tpke::share_combine_simple::<E>(&decryption_shares, &lagrange);
+            tpke::share_combine_simple::<E>(&decryption_shares, &lagrange)
+                .unwrap();
 
         assert_eq!(old_shared_secret, new_shared_secret);
     }
@@ -393,8 +537,85 @@ mod test_dkg_full {
         let new_shared_secret = tpke::share_combine_simple::<E>(
             &new_decryption_shares,
             &lagrange_coeffs,
-        );
+        )
+        .unwrap();
 
         assert_eq!(old_shared_secret, new_shared_secret);
     }
+
+    #[test]
+    fn test_dkg_simple_decryption_variant_bls12_377() {
+        type E = ark_bls12_377::Bls12_377;
+
+        let rng = &mut test_rng();
+        let shares_num = 4;
+        let params = Params {
+            tau: 0,
+            security_threshold: 3,
+            shares_num,
+        };
+        let keypairs = (0..shares_num)
+            .map(|_| Keypair::<E>::new(rng))
+            .collect::<Vec<_>>();
+        let validators = keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| ExternalValidator {
+                address: format!("validator_{}", i),
+                public_key: keypair.public(),
+            })
+            .collect::<Vec<_>>();
+        let new_dkg = |i: usize| {
+            PubliclyVerifiableDkg::new(
+                validators.clone(),
+                params,
+                &validators[i],
+                keypairs[i].clone(),
+            )
+            .unwrap()
+        };
+
+        let mut dkg = new_dkg(0);
+        for (i, validator) in validators.iter().enumerate() {
+            let pvss = new_dkg(i).share(rng).unwrap();
+            dkg.apply_message(validator.clone(), pvss).unwrap();
+        }
+
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let ciphertext = tpke::encrypt::<_, E>(msg, aad, &dkg.final_key(), rng);
+        let g_inv = dkg.pvss_params.g_inv();
+
+        let pvss_aggregated = aggregate(&dkg);
+        let decryption_shares = keypairs
+            .iter()
+            .enumerate()
+            .map(|(validator_index, keypair)| {
+                pvss_aggregated.make_decryption_share_simple(
+                    &ciphertext,
+                    aad,
+                    &keypair.decryption_key,
+                    validator_index,
+                    &g_inv,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let domain = &dkg.domain.elements().collect::<Vec<_>>();
+        let lagrange_coeffs = tpke::prepare_combine_simple::<E>(domain);
+        let shared_secret = tpke::share_combine_simple::<E>(
+            &decryption_shares,
+            &lagrange_coeffs,
+        )
+        .unwrap();
+
+        let plaintext = tpke::checked_decrypt_with_shared_secret(
+            &ciphertext,
+            aad,
+            &g_inv,
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(plaintext, msg);
+    }
 }
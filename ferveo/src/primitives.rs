@@ -1,5 +1,88 @@
+use crate::*;
+use ark_ff::UniformRand;
+use ark_serialize::*;
+use blake2::{Blake2s256, Digest};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use zeroize::{Zeroize, Zeroizing};
+
 pub fn batch_to_projective<A: ark_ec::AffineCurve>(
     p: &[A],
 ) -> Vec<A::Projective> {
     p.iter().map(|a| a.into_projective()).collect::<Vec<_>>()
 }
+
+/// A secret encrypted to the session key ek_i = [dk_i] H of a validator
+///
+/// The symmetric key is derived from [r] ek_i, which the validator recovers as [dk_i] R.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EncryptedShare<E: PairingEngine> {
+    /// R = [r] H
+    pub ephemeral_key: E::G2Affine,
+    pub ciphertext: Vec<u8>,
+}
+
+impl<E: PairingEngine> EncryptedShare<E> {
+    /// Encrypts `plaintext` to `ek`, authenticating `aad`, with keys domain separated by `dst`
+    pub fn encrypt<R: Rng>(
+        plaintext: &[u8],
+        aad: &[u8],
+        ek: &E::G2Affine,
+        h: &E::G2Affine,
+        dst: &[u8],
+        rng: &mut R,
+    ) -> Result<Self> {
+        let mut r = E::Fr::rand(rng);
+        let ephemeral_key = h.mul(r).into_affine();
+        let key = share_key::<E>(&ek.mul(r).into_affine(), dst)?;
+        r.zeroize();
+
+        // Every key encrypts a single message, so the nonce is fixed
+        let ciphertext = ChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| anyhow!("share encryption failed"))?;
+        Ok(Self {
+            ephemeral_key,
+            ciphertext,
+        })
+    }
+
+    /// Decrypts with the session decryption key `dk`
+    pub fn decrypt(
+        &self,
+        aad: &[u8],
+        dk: &E::Fr,
+        dst: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let shared = self.ephemeral_key.mul(*dk).into_affine();
+        let key = share_key::<E>(&shared, dst)?;
+        let plaintext = ChaCha20Poly1305::new(key.as_ref().into())
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| anyhow!("share decryption failed"))?;
+        Ok(Zeroizing::new(plaintext))
+    }
+}
+
+fn share_key<E: PairingEngine>(
+    shared: &E::G2Affine,
+    dst: &[u8],
+) -> Result<Zeroizing<[u8; 32]>> {
+    let mut shared_bytes = Zeroizing::new(vec![]);
+    shared.serialize(&mut *shared_bytes)?;
+    let mut hasher = Blake2s256::new();
+    hasher.update(dst);
+    hasher.update(&*shared_bytes);
+    Ok(Zeroizing::new(hasher.finalize().into()))
+}
@@ -1,7 +1,13 @@
+pub mod groth21;
+pub mod kzg_vss;
 pub mod pvss;
 
 use crate::*;
+use ark_serialize::*;
+pub use groth21::*;
+pub use kzg_vss::*;
 pub use pvss::*;
+use std::fmt::Debug;
 
 /// The possible States of a VSS instance
 #[derive(Clone, Debug)]
@@ -14,3 +20,40 @@ pub enum VssState<Affine: AffineCurve> {
     /// The VSS has ended in Failure
     Failure,
 }
+
+/// A PVSS scheme whose transcripts are dealt, verified and aggregated through the
+/// messages of a [`PubliclyVerifiableDkg`]
+pub trait PvssScheme<E: PairingEngine>:
+    Sized + Clone + Debug + CanonicalSerialize + CanonicalDeserialize
+{
+    /// The aggregation of the transcripts of a DKG
+    type Aggregated: Clone + Debug + CanonicalSerialize + CanonicalDeserialize;
+
+    /// Deal a transcript sharing `s` within the DKG session `dkg`
+    fn deal<R: Rng>(
+        s: &E::Fr,
+        dkg: &PubliclyVerifiableDkg<E, Self>,
+        rng: &mut R,
+    ) -> Result<Self>;
+
+    /// [s] G, the contribution of the dealer to the final key
+    fn secret_commitment(&self) -> E::G1Affine;
+
+    /// The checks that are cheap enough to run on every dealing
+    fn verify_optimistic(&self) -> bool;
+
+    /// Every check of the transcript that does not need a decryption key
+    fn verify_full(&self, dkg: &PubliclyVerifiableDkg<E, Self>) -> bool;
+
+    /// Aggregate the transcripts received by `dkg`
+    fn aggregate(
+        dkg: &PubliclyVerifiableDkg<E, Self>,
+    ) -> Result<Self::Aggregated>;
+
+    /// Verify that `aggregated` is the aggregation of the transcripts received by `dkg`
+    /// Returns the number of aggregated transcripts
+    fn verify_aggregation(
+        aggregated: &Self::Aggregated,
+        dkg: &PubliclyVerifiableDkg<E, Self>,
+    ) -> Result<u32>;
+}
@@ -0,0 +1,1268 @@
+use crate::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use ark_ec::msm::VariableBaseMSM;
+use ark_ff::{BigInteger, UniformRand};
+use ark_serialize::*;
+use blake2::{Blake2b512, Digest};
+use subproductdomain::fast_multiexp;
+use zeroize::Zeroize;
+
+/// Bits per chunk of an encrypted share
+pub const CHUNK_BITS: usize = 16;
+
+/// Domain separation of the Fiat-Shamir challenges of the sharing proof
+const SHARING_BATCH_DST: &[u8] = b"FERVEO-V01-GROTH21-SHARING-BATCH";
+const SHARING_CHALLENGE_DST: &[u8] = b"FERVEO-V01-GROTH21-SHARING-CHALLENGE";
+
+/// Domain separation of the Fiat-Shamir challenges of the chunking proof
+const CHUNKING_CHALLENGES_DST: &[u8] =
+    b"FERVEO-V01-GROTH21-CHUNKING-CHALLENGES";
+const CHUNKING_CHALLENGE_DST: &[u8] = b"FERVEO-V01-GROTH21-CHUNKING-CHALLENGE";
+
+/// Parallel repetitions of the chunking proof, each with challenges of one byte, for 256
+/// bits of soundness
+const CHUNKING_REPETITIONS: usize = 32;
+
+/// Attempts of the rejection sampling of the chunking proof, each succeeding with
+/// probability about 0.6 for honest dealers
+const CHUNKING_ATTEMPTS: usize = 64;
+
+/// Type alias for non aggregated Groth21 PVSS transcripts
+pub type Groth21Pvss<E> = Groth21VerifiableSS<E>;
+
+/// Type alias for aggregated Groth21 PVSS transcripts
+pub type AggregatedGroth21Pvss<E> = Groth21VerifiableSS<E, Aggregated>;
+
+/// Type alias for a DKG dealing Groth21 PVSS transcripts
+pub type Groth21Dkg<E> = PubliclyVerifiableDkg<E, Groth21Pvss<E>>;
+
+/// The share of one validator, split into chunks of [`CHUNK_BITS`] bits that are
+/// ElGamal-encrypted to its session key
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq)]
+pub struct ChunkedCiphertext<E: PairingEngine> {
+    /// C_{i,j} = [r_j] ek_i + [s_{i,j}] H
+    pub chunks: Vec<E::G2Affine>,
+}
+
+/// Non-interactive proof that the chunked ciphertexts encrypt the evaluations of the
+/// polynomial committed to by the Feldman commitment
+///
+/// With the chunks recombined into C_i = [r] ek_i + [s_i] H and R = [r] H, and a
+/// challenge x batching the validators, this proves knowledge of r and s* = \sum_i x^i s_i
+/// such that R = [r] H, \sum_i x^i C_i = [r] \sum_i x^i ek_i + [s*] H and
+/// \sum_i x^i A_i = [s*] G, where A_i are the public key shares of the commitment.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq)]
+pub struct SharingProof<E: PairingEngine> {
+    /// [ρ] H
+    pub f: E::G2Affine,
+    /// [α] G
+    pub a: E::G1Affine,
+    /// [ρ] EK* + [α] H
+    pub y: E::G2Affine,
+    pub z_r: E::Fr,
+    pub z_s: E::Fr,
+}
+
+/// Non-interactive approximate range proof of the chunks encrypted to the validators,
+/// the chunking proof of section 6.5 of Groth21
+///
+/// With n validators, m chunks and `CHUNKING_REPETITIONS` ℓ, let S = n m (2^B - 1) 255 and
+/// Z = 2 ℓ S. The proof shows that for each chunk s_{i,j} there is a Δ in [1, 256) with
+/// Δ s_{i,j} in (-Z, Z), so that validators can decrypt it, see
+/// [`Groth21VerifiableSS::decrypt_private_key_share`]. Honest dealers, with chunks in
+/// [0, 2^B), always pass.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq)]
+pub struct ChunkingProof<E: PairingEngine> {
+    /// A random point y_0
+    pub y0: E::G2Affine,
+    /// B_k = [β_k] H, for each repetition k
+    pub b: Vec<E::G2Affine>,
+    /// C_k = [β_k] y_0 + [σ_k] H, with σ_k in [-S, Z)
+    pub c: Vec<E::G2Affine>,
+    /// D_i = [δ_i] H, for i = 0..n
+    pub d: Vec<E::G2Affine>,
+    /// Y = [δ_0] y_0 + \sum_i [δ_i] ek_i
+    pub y: E::G2Affine,
+    /// z_{r,i} = \sum_{j,k} x^k e_{i,j,k} r_j + δ_i
+    pub z_r: Vec<E::Fr>,
+    /// z_{s,k} = \sum_{i,j} e_{i,j,k} s_{i,j} + σ_k, in [0, Z)
+    pub z_s: Vec<u64>,
+    /// z_β = \sum_k x^k β_k + δ_0
+    pub z_beta: E::Fr,
+}
+
+/// A PVSS transcript in the style of Groth21 (https://eprint.iacr.org/2021/339), with
+/// private key shares in E::Fr instead of G2
+///
+/// Shares are encrypted with chunked ElGamal, so that validators decrypt them by solving
+/// small discrete logarithms. The [`ChunkingProof`] shows the chunks are small enough to
+/// decrypt, and the [`SharingProof`] that they recombine to the committed shares.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct Groth21VerifiableSS<E: PairingEngine, T = Unaggregated> {
+    /// Used in Feldman commitment to the VSS polynomial, F = g^{\phi}
+    pub coeffs: Vec<E::G1Affine>,
+
+    /// R_j = [r_j] H, the ElGamal randomness of the j-th chunk of every share
+    pub randomizers: Vec<E::G2Affine>,
+
+    /// The encrypted shares of each validator
+    pub shares: Vec<ChunkedCiphertext<E>>,
+
+    /// Proof of Knowledge
+    pub sigma: E::G2Affine,
+
+    /// Only present before aggregation, since proofs do not aggregate
+    pub chunking_proof: Option<ChunkingProof<E>>,
+
+    /// Only present before aggregation, like the chunking proof
+    pub sharing_proof: Option<SharingProof<E>>,
+
+    /// The number of transcripts aggregated into this one, which bounds the chunks
+    pub dealings: u32,
+
+    /// Marker struct to distinguish between aggregated and
+    /// non aggregated PVSS transcripts
+    phantom: PhantomData<T>,
+}
+
+impl<E: PairingEngine> Groth21VerifiableSS<E> {
+    /// Create a new PVSS instance
+    /// `s`: the secret constant coefficient to share
+    /// `dkg`: the current DKG session
+    /// `rng` a cryptographic random number generator
+    pub fn new<R: Rng>(
+        s: &E::Fr,
+        dkg: &Groth21Dkg<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::new_with_chunking(s, dkg, rng, to_chunks)
+    }
+
+    /// Like [`Groth21VerifiableSS::new`], splitting the shares with `chunking`
+    fn new_with_chunking<R: Rng>(
+        s: &E::Fr,
+        dkg: &Groth21Dkg<E>,
+        rng: &mut R,
+        chunking: impl Fn(&E::Fr) -> Vec<E::Fr> + Sync,
+    ) -> Result<Self> {
+        let h = dkg.pvss_params.h;
+        // Our random polynomial, \phi(x) = s + \sum_{i=1}^{t-1} a_i x^i
+        let mut phi = DensePolynomial::<E::Fr>::rand(
+            (dkg.params.security_threshold - 1) as usize,
+            rng,
+        );
+        phi.coeffs[0] = *s;
+        let mut evals = phi.evaluate_over_domain_by_ref(dkg.domain);
+        let coeffs = fast_multiexp(&phi.coeffs, dkg.pvss_params.g);
+        phi.coeffs.zeroize();
+
+        let mut r = (0..num_chunks::<E::Fr>())
+            .map(|_| E::Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let randomizers = fast_multiexp(&r, h);
+        let mut chunks = cfg_iter!(dkg.validators)
+            .map(|val| chunking(&evals.evals[val.share_index]))
+            .collect::<Vec<_>>();
+        let shares = cfg_iter!(dkg.validators)
+            .zip(cfg_iter!(chunks))
+            .map(|(val, chunks)| {
+                // [r_j] ek_i + [s_{i,j}] H
+                let masks = fast_multiexp(
+                    &r,
+                    val.validator.public_key.encryption_key.into_projective(),
+                );
+                let messages = fast_multiexp(chunks, h);
+                let chunks = masks
+                    .iter()
+                    .zip(messages.iter())
+                    .map(|(mask, message)| {
+                        mask.into_projective() + message.into_projective()
+                    })
+                    .collect::<Vec<_>>();
+                ChunkedCiphertext {
+                    chunks: E::G2Projective::batch_normalization_into_affine(
+                        &chunks,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut transcript = Self {
+            coeffs,
+            randomizers,
+            shares,
+            sigma: E::G2Affine::prime_subgroup_generator().mul(*s).into(),
+            chunking_proof: None,
+            sharing_proof: None,
+            dealings: 1,
+            phantom: Default::default(),
+        };
+        let chunking_proof = transcript.prove_chunking(dkg, &r, &chunks, rng);
+        chunks.iter_mut().for_each(|c| c.zeroize());
+        transcript.chunking_proof = Some(chunking_proof?);
+
+        // r = \sum_j 2^{jB} r_j, s* = \sum_i x^i s_i
+        let mut r_combined = recombine(&r);
+        r.zeroize();
+        let x = transcript.batch_challenge(dkg.params.tau)?;
+        let mut s_combined = dkg
+            .validators
+            .iter()
+            .zip(powers(&x, dkg.validators.len()))
+            .map(|(val, x_i)| evals.evals[val.share_index] * x_i)
+            .sum::<E::Fr>();
+        evals.evals.zeroize();
+
+        let statement = transcript.sharing_statement(dkg, &x);
+        let mut rho = E::Fr::rand(rng);
+        let mut alpha = E::Fr::rand(rng);
+        let f = h.into_affine().mul(rho).into_affine();
+        let a = dkg.pvss_params.g.into_affine().mul(alpha).into_affine();
+        let y =
+            (statement.ek.mul(rho) + h.into_affine().mul(alpha)).into_affine();
+        let c = sharing_challenge(&x, &statement, &f, &a, &y)?;
+        transcript.sharing_proof = Some(SharingProof {
+            f,
+            a,
+            y,
+            z_r: c * r_combined + rho,
+            z_s: c * s_combined + alpha,
+        });
+        r_combined.zeroize();
+        s_combined.zeroize();
+        rho.zeroize();
+        alpha.zeroize();
+        Ok(transcript)
+    }
+
+    /// Verify the pvss transcript from a validator. This is not the full check,
+    /// i.e. we optimistically do not check the sharing proof
+    pub fn verify_optimistic(&self) -> bool {
+        // sigma ?= h^s
+        E::pairing(
+            self.coeffs[0].into_projective(),
+            E::G2Affine::prime_subgroup_generator(),
+        ) == E::pairing(E::G1Affine::prime_subgroup_generator(), self.sigma)
+    }
+
+    /// Checks the chunking proof, that the chunks are small enough to decrypt, and the
+    /// sharing proof, that the recombined ciphertexts encrypt the evaluations of the
+    /// committed polynomial
+    pub fn verify_full(&self, dkg: &Groth21Dkg<E>) -> bool {
+        let num_chunks = num_chunks::<E::Fr>();
+        if self.dealings != 1
+            || self.randomizers.len() != num_chunks
+            || self.shares.len() != dkg.validators.len()
+            || self.shares.iter().any(|c| c.chunks.len() != num_chunks)
+        {
+            return false;
+        }
+        let valid_chunks = match &self.chunking_proof {
+            Some(proof) => self.verify_chunking_proof(dkg, proof),
+            None => false,
+        };
+        let proof = match &self.sharing_proof {
+            Some(proof) if valid_chunks => proof,
+            _ => return false,
+        };
+        let x = match self.batch_challenge(dkg.params.tau) {
+            Ok(x) => x,
+            Err(_) => return false,
+        };
+        let statement = self.sharing_statement(dkg, &x);
+        let c = match sharing_challenge(
+            &x, &statement, &proof.f, &proof.a, &proof.y,
+        ) {
+            Ok(c) => c,
+            Err(_) => return false,
+        };
+
+        let g = dkg.pvss_params.g.into_affine();
+        let h = dkg.pvss_params.h.into_affine();
+        // [c] R + F = [z_r] H
+        statement.r.mul(c) + proof.f.into_projective() == h.mul(proof.z_r)
+            // [c] A* + A' = [z_s] G
+            && statement.a.mul(c) + proof.a.into_projective()
+                == g.mul(proof.z_s)
+            // [c] C* + Y = [z_r] EK* + [z_s] H
+            && statement.c.mul(c) + proof.y.into_projective()
+                == statement.ek.mul(proof.z_r) + h.mul(proof.z_s)
+    }
+
+    /// Proves that the chunks of every share, encrypted with the randomness `r`, are small
+    fn prove_chunking<R: Rng>(
+        &self,
+        dkg: &Groth21Dkg<E>,
+        r: &[E::Fr],
+        chunks: &[Vec<E::Fr>],
+        rng: &mut R,
+    ) -> Result<ChunkingProof<E>> {
+        let (s_bound, z_bound) = chunking_bounds(dkg.validators.len(), r.len())
+            .ok_or_else(|| anyhow!("too many validators to prove chunking"))?;
+        let out_of_range = || anyhow!("chunks are out of range");
+        let mut s = chunks
+            .iter()
+            .flatten()
+            .map(chunk_to_integer)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(out_of_range)?;
+        let h = dkg.pvss_params.h.into_affine();
+        let encryption_keys = encryption_keys(dkg);
+        let y0 = h.mul(E::Fr::rand(rng)).into_affine();
+
+        // Rejection sampling, so that z_{s,k} does not depend on the chunks
+        for _ in 0..CHUNKING_ATTEMPTS {
+            let mut beta = (0..CHUNKING_REPETITIONS)
+                .map(|_| E::Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let mut sigma = (0..CHUNKING_REPETITIONS)
+                .map(|_| {
+                    ark_std::rand::Rng::gen_range(rng, 0..z_bound + s_bound)
+                        as i128
+                        - s_bound as i128
+                })
+                .collect::<Vec<_>>();
+            let b = fast_multiexp(&beta, h.into_projective());
+            let c = E::G2Projective::batch_normalization_into_affine(
+                &beta
+                    .iter()
+                    .zip(sigma.iter())
+                    .map(|(beta_k, sigma_k)| {
+                        y0.mul(*beta_k)
+                            + h.mul(integer_to_field::<E::Fr>(*sigma_k))
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            let first_move = self.chunking_first_move(
+                dkg.params.tau,
+                &encryption_keys,
+                &y0,
+                &b,
+                &c,
+            )?;
+            let e = chunking_challenges(&first_move, s.len());
+            let z_s = e
+                .chunks(s.len())
+                .zip(sigma.iter())
+                .map(|(e_k, sigma_k)| {
+                    e_k.iter()
+                        .zip(s.iter())
+                        .map(|(e, s)| i128::from(*e) * s)
+                        .sum::<i128>()
+                        + sigma_k
+                })
+                .map(|z| (0..z_bound as i128).contains(&z).then_some(z as u64))
+                .collect::<Option<Vec<_>>>();
+            sigma.zeroize();
+            let z_s = match z_s {
+                Some(z_s) => z_s,
+                None => {
+                    beta.zeroize();
+                    continue;
+                }
+            };
+
+            let mut delta = (0..=dkg.validators.len())
+                .map(|_| E::Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let d = fast_multiexp(&delta, h.into_projective());
+            let y = (y0.mul(delta[0])
+                + VariableBaseMSM::multi_scalar_mul(
+                    &encryption_keys,
+                    &delta[1..]
+                        .iter()
+                        .map(|d| d.into_repr())
+                        .collect::<Vec<_>>(),
+                ))
+            .into_affine();
+            let x = chunking_challenge::<E>(&first_move, &z_s, &d, &y)?;
+            let x_powers = powers(&x, CHUNKING_REPETITIONS).collect::<Vec<_>>();
+            let e_x = challenge_sums(&e, &x_powers, s.len());
+            s.zeroize();
+
+            let proof = ChunkingProof {
+                y0,
+                b,
+                c,
+                y,
+                z_r: e_x
+                    .chunks(r.len())
+                    .zip(delta[1..].iter())
+                    .map(|(e_i, delta_i)| {
+                        e_i.iter()
+                            .zip(r)
+                            .map(|(e, r_j)| *e * r_j)
+                            .sum::<E::Fr>()
+                            + delta_i
+                    })
+                    .collect(),
+                z_s,
+                z_beta: x_powers
+                    .iter()
+                    .zip(beta.iter())
+                    .map(|(x_k, beta_k)| *x_k * beta_k)
+                    .sum::<E::Fr>()
+                    + delta[0],
+                d,
+            };
+            beta.zeroize();
+            delta.zeroize();
+            return Ok(proof);
+        }
+        s.zeroize();
+        Err(out_of_range())
+    }
+
+    /// Checks the chunking proof against the chunks of this transcript
+    fn verify_chunking_proof(
+        &self,
+        dkg: &Groth21Dkg<E>,
+        proof: &ChunkingProof<E>,
+    ) -> bool {
+        let n = dkg.validators.len();
+        let m = self.randomizers.len();
+        let z_bound = match chunking_bounds(n, m) {
+            Some((_, z_bound)) => z_bound,
+            None => return false,
+        };
+        if proof.b.len() != CHUNKING_REPETITIONS
+            || proof.c.len() != CHUNKING_REPETITIONS
+            || proof.d.len() != n + 1
+            || proof.z_r.len() != n
+            || proof.z_s.len() != CHUNKING_REPETITIONS
+            || proof.z_s.iter().any(|z| *z >= z_bound)
+        {
+            return false;
+        }
+        let encryption_keys = encryption_keys(dkg);
+        let first_move = match self.chunking_first_move(
+            dkg.params.tau,
+            &encryption_keys,
+            &proof.y0,
+            &proof.b,
+            &proof.c,
+        ) {
+            Ok(first_move) => first_move,
+            Err(_) => return false,
+        };
+        let e = chunking_challenges(&first_move, n * m);
+        let x = match chunking_challenge::<E>(
+            &first_move,
+            &proof.z_s,
+            &proof.d,
+            &proof.y,
+        ) {
+            Ok(x) => x,
+            Err(_) => return false,
+        };
+        let x_powers = powers(&x, CHUNKING_REPETITIONS).collect::<Vec<_>>();
+        let e_x = challenge_sums(&e, &x_powers, n * m)
+            .iter()
+            .map(|e| e.into_repr())
+            .collect::<Vec<_>>();
+        let to_repr = |scalars: &[E::Fr]| {
+            scalars.iter().map(|s| s.into_repr()).collect::<Vec<_>>()
+        };
+
+        let h = dkg.pvss_params.h.into_affine();
+        // [z_{r,i}] H = \sum_j [\sum_k x^k e_{i,j,k}] R_j + D_i
+        let valid_randomizers = e_x
+            .chunks(m)
+            .zip(proof.z_r.iter().zip(proof.d[1..].iter()))
+            .all(|(e_i, (z_r, d_i))| {
+                h.mul(*z_r)
+                    == VariableBaseMSM::multi_scalar_mul(&self.randomizers, e_i)
+                        + d_i.into_projective()
+            });
+        // [z_β] H = \sum_k [x^k] B_k + D_0
+        let valid_beta = h.mul(proof.z_beta)
+            == VariableBaseMSM::multi_scalar_mul(&proof.b, &to_repr(&x_powers))
+                + proof.d[0].into_projective();
+        if !valid_randomizers || !valid_beta {
+            return false;
+        }
+        // \sum_{i,j} [\sum_k x^k e_{i,j,k}] C_{i,j} + \sum_k [x^k] C_k + Y
+        //   = \sum_i [z_{r,i}] ek_i + [z_β] y_0 + [\sum_k x^k z_{s,k}] H
+        let ciphertexts = self
+            .shares
+            .iter()
+            .flat_map(|c| c.chunks.iter().copied())
+            .collect::<Vec<_>>();
+        let z_s = x_powers
+            .iter()
+            .zip(proof.z_s.iter())
+            .map(|(x_k, z_k)| *x_k * E::Fr::from(*z_k))
+            .sum::<E::Fr>();
+        VariableBaseMSM::multi_scalar_mul(&ciphertexts, &e_x)
+            + VariableBaseMSM::multi_scalar_mul(&proof.c, &to_repr(&x_powers))
+            + proof.y.into_projective()
+            == VariableBaseMSM::multi_scalar_mul(
+                &encryption_keys,
+                &to_repr(&proof.z_r),
+            ) + proof.y0.mul(proof.z_beta)
+                + h.mul(z_s)
+    }
+
+    /// The statement and first move of the chunking proof, which its challenges hash
+    fn chunking_first_move(
+        &self,
+        tau: u64,
+        encryption_keys: &[E::G2Affine],
+        y0: &E::G2Affine,
+        b: &[E::G2Affine],
+        c: &[E::G2Affine],
+    ) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        CanonicalSerialize::serialize(&tau, &mut bytes)?;
+        CanonicalSerialize::serialize(encryption_keys, &mut bytes)?;
+        CanonicalSerialize::serialize(&self.randomizers, &mut bytes)?;
+        CanonicalSerialize::serialize(&self.shares, &mut bytes)?;
+        CanonicalSerialize::serialize(y0, &mut bytes)?;
+        CanonicalSerialize::serialize(b, &mut bytes)?;
+        CanonicalSerialize::serialize(c, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// The challenge x batching the validators in the sharing proof
+    fn batch_challenge(&self, tau: u64) -> Result<E::Fr> {
+        let mut bytes = vec![];
+        CanonicalSerialize::serialize(&tau, &mut bytes)?;
+        CanonicalSerialize::serialize(&self.coeffs, &mut bytes)?;
+        CanonicalSerialize::serialize(&self.randomizers, &mut bytes)?;
+        CanonicalSerialize::serialize(&self.shares, &mut bytes)?;
+        Ok(hash_to_field(SHARING_BATCH_DST, &bytes))
+    }
+
+    /// R, \sum_i x^i C_i, \sum_i x^i ek_i and \sum_i x^i A_i
+    fn sharing_statement(
+        &self,
+        dkg: &Groth21Dkg<E>,
+        x: &E::Fr,
+    ) -> SharingStatement<E> {
+        let chunk_scalars = chunk_scalars::<E::Fr>();
+        let combine = |points: &[E::G2Affine]| {
+            VariableBaseMSM::multi_scalar_mul(points, &chunk_scalars)
+                .into_affine()
+        };
+        let ciphertexts = cfg_iter!(self.shares)
+            .map(|c| combine(&c.chunks))
+            .collect::<Vec<_>>();
+
+        let mut commitment = batch_to_projective(&self.coeffs);
+        dkg.domain.fft_in_place(&mut commitment);
+        let public_key_shares =
+            E::G1Projective::batch_normalization_into_affine(
+                &dkg.validators
+                    .iter()
+                    .map(|v| commitment[v.share_index])
+                    .collect::<Vec<_>>(),
+            );
+        let encryption_keys = encryption_keys(dkg);
+
+        let x_powers = powers(x, dkg.validators.len())
+            .map(|x_i| x_i.into_repr())
+            .collect::<Vec<_>>();
+        SharingStatement {
+            r: combine(&self.randomizers),
+            c: VariableBaseMSM::multi_scalar_mul(&ciphertexts, &x_powers)
+                .into_affine(),
+            ek: VariableBaseMSM::multi_scalar_mul(&encryption_keys, &x_powers)
+                .into_affine(),
+            a: VariableBaseMSM::multi_scalar_mul(&public_key_shares, &x_powers)
+                .into_affine(),
+        }
+    }
+}
+
+/// Extra methods available to aggregated PVSS transcripts
+impl<E: PairingEngine, T: Aggregate> Groth21VerifiableSS<E, T> {
+    /// Verify that this PVSS instance is a valid aggregation of
+    /// the PVSS instances, produced by [`aggregate_groth21`],
+    /// and received by the DKG context `dkg`
+    /// Returns the total valid weight of the aggregated PVSS
+    pub fn verify_aggregation(&self, dkg: &Groth21Dkg<E>) -> Result<u32> {
+        if let Some(dealer) = dkg
+            .vss
+            .iter()
+            .find(|(_, t)| !t.verify_optimistic() || !t.verify_full(dkg))
+            .map(|(dealer, _)| dealer)
+        {
+            return Err(anyhow!(
+                "PVSS transcript of dealer {} is invalid",
+                dealer
+            ));
+        }
+        let expected = aggregate_groth21(dkg)?;
+        if self.coeffs == expected.coeffs
+            && self.randomizers == expected.randomizers
+            && self.shares == expected.shares
+            && self.sigma == expected.sigma
+            && self.dealings == expected.dealings
+        {
+            Ok(self.dealings)
+        } else {
+            Err(anyhow!(
+                "aggregation does not match received PVSS instances"
+            ))
+        }
+    }
+}
+
+impl<E: PairingEngine, T> Groth21VerifiableSS<E, T> {
+    /// Decrypts the share s_i of validator `validator_index`, by solving the discrete
+    /// logarithm of every chunk
+    ///
+    /// Chunks of honest dealers are below 2^B, and solved quickly. Chunks the chunking proof
+    /// only bounds approximately take a search of the size of its bounds: the chunks of an
+    /// aggregation are then decrypted transcript by transcript from `dkg`.
+    pub fn decrypt_private_key_share(
+        &self,
+        dkg: &Groth21Dkg<E>,
+        validator_decryption_key: &E::Fr,
+        validator_index: usize,
+    ) -> Result<E::Fr> {
+        let ciphertext = self
+            .shares
+            .get(validator_index)
+            .ok_or_else(|| anyhow!("unknown validator {}", validator_index))?;
+        let h = dkg.pvss_params.h.into_affine();
+        // Every aggregated transcript adds up to 2^B - 1 to a chunk
+        let bound = (self.dealings.max(1) as u64) << CHUNK_BITS;
+        let baby_steps = BabySteps::new(h, bound);
+
+        // [s_{i,j}] H = C_{i,j} - [dk_i] R_j
+        let messages = ciphertext
+            .chunks
+            .iter()
+            .zip(self.randomizers.iter())
+            .map(|(c_j, r_j)| {
+                c_j.into_projective() - r_j.mul(*validator_decryption_key)
+            })
+            .collect::<Vec<_>>();
+        let chunks = messages
+            .iter()
+            .map(|m| baby_steps.discrete_log(*m).map(E::Fr::from))
+            .collect::<Option<Vec<_>>>();
+        let mut chunks = match chunks {
+            Some(chunks) => chunks,
+            None if self.dealings > 1 => {
+                return self.decrypt_dealings(
+                    dkg,
+                    validator_decryption_key,
+                    validator_index,
+                )
+            }
+            None => {
+                let (_, z_bound) =
+                    chunking_bounds(dkg.validators.len(), messages.len())
+                        .ok_or_else(|| anyhow!("chunk is out of range"))?;
+                let oversized_steps = BabySteps::new(h, 2 * z_bound - 1);
+                messages
+                    .iter()
+                    .map(|m| {
+                        baby_steps
+                            .discrete_log(*m)
+                            .map(E::Fr::from)
+                            .or_else(|| {
+                                solve_oversized_chunk::<E>(
+                                    &oversized_steps,
+                                    z_bound,
+                                    *m,
+                                )
+                            })
+                            .ok_or_else(|| anyhow!("chunk is out of range"))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+        };
+        let share = recombine(&chunks);
+        chunks.zeroize();
+        Ok(share)
+    }
+
+    /// Decrypts the share of validator `validator_index` from each transcript of `dkg`,
+    /// and checks their sum against the commitment of this aggregation
+    fn decrypt_dealings(
+        &self,
+        dkg: &Groth21Dkg<E>,
+        validator_decryption_key: &E::Fr,
+        validator_index: usize,
+    ) -> Result<E::Fr> {
+        let share = dkg
+            .vss
+            .values()
+            .map(|t| {
+                t.decrypt_private_key_share(
+                    dkg,
+                    validator_decryption_key,
+                    validator_index,
+                )
+            })
+            .sum::<Result<E::Fr>>()?;
+        // [s_i] G = \sum_k [ω_i^k] F_k
+        let share_index = dkg.validators[validator_index].share_index;
+        let omega_powers =
+            powers(&dkg.domain.element(share_index), self.coeffs.len())
+                .map(|p| p.into_repr())
+                .collect::<Vec<_>>();
+        if VariableBaseMSM::multi_scalar_mul(&self.coeffs, &omega_powers)
+            == dkg.pvss_params.g.into_affine().mul(share)
+        {
+            Ok(share)
+        } else {
+            Err(anyhow!("PVSS transcripts do not match the aggregation"))
+        }
+    }
+}
+
+/// Aggregate the Groth21 PVSS instances from DKG session `dkg` into a new PVSS instance
+///
+/// ElGamal ciphertexts add up chunkwise, so the aggregation encrypts the sum of shares.
+pub fn aggregate_groth21<E: PairingEngine>(
+    dkg: &Groth21Dkg<E>,
+) -> Result<AggregatedGroth21Pvss<E>> {
+    let mut transcripts = dkg.vss.values();
+    let first = transcripts
+        .next()
+        .ok_or_else(|| anyhow!("no PVSS transcripts to aggregate"))?;
+    let mut coeffs = batch_to_projective(&first.coeffs);
+    let mut randomizers = batch_to_projective(&first.randomizers);
+    let mut shares = first
+        .shares
+        .iter()
+        .map(|c| batch_to_projective(&c.chunks))
+        .collect::<Vec<_>>();
+    let mut sigma = first.sigma.into_projective();
+    let mut dealings = first.dealings;
+
+    let sum_into = |a: &mut Vec<E::G2Projective>, b: &[E::G2Affine]| {
+        if a.len() != b.len() {
+            return Err(anyhow!("PVSS transcripts have different shapes"));
+        }
+        a.iter_mut()
+            .zip(b)
+            .for_each(|(a, b)| *a += b.into_projective());
+        Ok(())
+    };
+    for next in transcripts {
+        if coeffs.len() != next.coeffs.len()
+            || shares.len() != next.shares.len()
+        {
+            return Err(anyhow!("PVSS transcripts have different shapes"));
+        }
+        coeffs
+            .iter_mut()
+            .zip(next.coeffs.iter())
+            .for_each(|(a, b)| *a += b.into_projective());
+        sum_into(&mut randomizers, &next.randomizers)?;
+        for (a, b) in shares.iter_mut().zip(next.shares.iter()) {
+            sum_into(a, &b.chunks)?;
+        }
+        sigma += next.sigma.into_projective();
+        dealings += next.dealings;
+    }
+
+    Ok(Groth21VerifiableSS {
+        coeffs: E::G1Projective::batch_normalization_into_affine(&coeffs),
+        randomizers: E::G2Projective::batch_normalization_into_affine(
+            &randomizers,
+        ),
+        shares: shares
+            .iter()
+            .map(|c| ChunkedCiphertext {
+                chunks: E::G2Projective::batch_normalization_into_affine(c),
+            })
+            .collect(),
+        sigma: sigma.into_affine(),
+        chunking_proof: None,
+        sharing_proof: None,
+        dealings,
+        phantom: Default::default(),
+    })
+}
+
+impl<E: PairingEngine> PvssScheme<E> for Groth21VerifiableSS<E> {
+    type Aggregated = AggregatedGroth21Pvss<E>;
+
+    fn deal<R: Rng>(
+        s: &E::Fr,
+        dkg: &Groth21Dkg<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::new(s, dkg, rng)
+    }
+
+    fn secret_commitment(&self) -> E::G1Affine {
+        self.coeffs[0]
+    }
+
+    fn verify_optimistic(&self) -> bool {
+        Groth21VerifiableSS::verify_optimistic(self)
+    }
+
+    fn verify_full(&self, dkg: &Groth21Dkg<E>) -> bool {
+        Groth21VerifiableSS::verify_full(self, dkg)
+    }
+
+    fn aggregate(dkg: &Groth21Dkg<E>) -> Result<AggregatedGroth21Pvss<E>> {
+        aggregate_groth21(dkg)
+    }
+
+    fn verify_aggregation(
+        aggregated: &AggregatedGroth21Pvss<E>,
+        dkg: &Groth21Dkg<E>,
+    ) -> Result<u32> {
+        aggregated.verify_aggregation(dkg)
+    }
+}
+
+/// The public values the sharing proof is about
+struct SharingStatement<E: PairingEngine> {
+    r: E::G2Affine,
+    c: E::G2Affine,
+    ek: E::G2Affine,
+    a: E::G1Affine,
+}
+
+fn sharing_challenge<E: PairingEngine>(
+    x: &E::Fr,
+    statement: &SharingStatement<E>,
+    f: &E::G2Affine,
+    a: &E::G1Affine,
+    y: &E::G2Affine,
+) -> Result<E::Fr> {
+    let mut bytes = vec![];
+    CanonicalSerialize::serialize(x, &mut bytes)?;
+    CanonicalSerialize::serialize(&statement.r, &mut bytes)?;
+    CanonicalSerialize::serialize(&statement.c, &mut bytes)?;
+    CanonicalSerialize::serialize(&statement.ek, &mut bytes)?;
+    CanonicalSerialize::serialize(&statement.a, &mut bytes)?;
+    CanonicalSerialize::serialize(f, &mut bytes)?;
+    CanonicalSerialize::serialize(a, &mut bytes)?;
+    CanonicalSerialize::serialize(y, &mut bytes)?;
+    Ok(hash_to_field(SHARING_CHALLENGE_DST, &bytes))
+}
+
+/// The challenge x of the chunking proof
+fn chunking_challenge<E: PairingEngine>(
+    first_move: &[u8],
+    z_s: &[u64],
+    d: &[E::G2Affine],
+    y: &E::G2Affine,
+) -> Result<E::Fr> {
+    let mut bytes = first_move.to_vec();
+    CanonicalSerialize::serialize(z_s, &mut bytes)?;
+    CanonicalSerialize::serialize(d, &mut bytes)?;
+    CanonicalSerialize::serialize(y, &mut bytes)?;
+    Ok(hash_to_field(CHUNKING_CHALLENGE_DST, &bytes))
+}
+
+/// The challenges e_{i,j,k} of the chunking proof, a byte for each of the `count` chunks in
+/// each repetition k
+fn chunking_challenges(first_move: &[u8], count: usize) -> Vec<u8> {
+    let seed = Blake2b512::new()
+        .chain_update(CHUNKING_CHALLENGES_DST)
+        .chain_update(first_move)
+        .finalize();
+    (0u64..)
+        .flat_map(|block| {
+            Blake2b512::new()
+                .chain_update(seed)
+                .chain_update(block.to_le_bytes())
+                .finalize()
+        })
+        .take(CHUNKING_REPETITIONS * count)
+        .collect()
+}
+
+/// \sum_k x^k e_{i,j,k} for each of the `count` chunks
+fn challenge_sums<F: PrimeField>(
+    e: &[u8],
+    x_powers: &[F],
+    count: usize,
+) -> Vec<F> {
+    e.chunks(count).zip(x_powers).fold(
+        vec![F::zero(); count],
+        |mut sums, (e_k, x_k)| {
+            sums.iter_mut()
+                .zip(e_k)
+                .for_each(|(sum, e)| *sum += F::from(u64::from(*e)) * x_k);
+            sums
+        },
+    )
+}
+
+/// S = n m (2^B - 1) 255 and Z = 2 ℓ S, the bounds of the chunking proof for `validators`
+/// shares of `chunks` chunks
+fn chunking_bounds(validators: usize, chunks: usize) -> Option<(u64, u64)> {
+    let s = (validators as u64)
+        .checked_mul(chunks as u64)?
+        .checked_mul((1 << CHUNK_BITS) - 1)?
+        .checked_mul(u8::MAX as u64)?;
+    let z = s.checked_mul(2 * CHUNKING_REPETITIONS as u64)?;
+    // σ_k is sampled below Z + S
+    z.checked_add(s)?;
+    Some((s, z))
+}
+
+/// The chunk of a dealer as an integer, if it is of magnitude below 2^64
+fn chunk_to_integer<F: PrimeField>(chunk: &F) -> Option<i128> {
+    let small = |f: F| {
+        let repr = f.into_repr();
+        let limbs = repr.as_ref();
+        limbs[1..]
+            .iter()
+            .all(|limb| *limb == 0)
+            .then(|| i128::from(limbs[0]))
+    };
+    small(*chunk).or_else(|| small(-*chunk).map(|v| -v))
+}
+
+fn integer_to_field<F: PrimeField>(v: i128) -> F {
+    if v < 0 {
+        -F::from(v.unsigned_abs())
+    } else {
+        F::from(v as u128)
+    }
+}
+
+/// Solves a chunk outside of [0, 2^B) from its message [s_{i,j}] H: by the chunking proof,
+/// there is a Δ in [1, 256) with Δ s_{i,j} in (-Z, Z). `baby_steps` solve below 2Z - 1.
+fn solve_oversized_chunk<E: PairingEngine>(
+    baby_steps: &BabySteps<E::G2Affine>,
+    z_bound: u64,
+    message: E::G2Projective,
+) -> Option<E::Fr> {
+    let shift = baby_steps.base.mul(z_bound - 1);
+    (1..=u8::MAX as u64).find_map(|delta| {
+        // [Δ s_{i,j} + Z - 1] H
+        let target = message.mul(E::Fr::from(delta).into_repr()) + shift;
+        baby_steps.discrete_log(target).map(|log| {
+            integer_to_field::<E::Fr>(log as i128 - (z_bound - 1) as i128)
+                * E::Fr::from(delta).inverse().unwrap()
+        })
+    })
+}
+
+fn encryption_keys<E: PairingEngine>(dkg: &Groth21Dkg<E>) -> Vec<E::G2Affine> {
+    dkg.validators
+        .iter()
+        .map(|v| v.validator.public_key.encryption_key)
+        .collect()
+}
+
+fn hash_to_field<F: PrimeField>(dst: &[u8], bytes: &[u8]) -> F {
+    let mut hasher = Blake2b512::new();
+    hasher.update(dst);
+    hasher.update(bytes);
+    F::from_le_bytes_mod_order(&hasher.finalize())
+}
+
+fn num_chunks<F: PrimeField>() -> usize {
+    F::size_in_bits().div_ceil(CHUNK_BITS)
+}
+
+/// The little-endian chunks of [`CHUNK_BITS`] bits of `s`
+fn to_chunks<F: PrimeField>(s: &F) -> Vec<F> {
+    let bits = s.into_repr().to_bits_le();
+    bits.chunks(CHUNK_BITS)
+        .take(num_chunks::<F>())
+        .map(|chunk| {
+            let value = chunk
+                .iter()
+                .rev()
+                .fold(0u64, |acc, bit| (acc << 1) | *bit as u64);
+            F::from(value)
+        })
+        .collect()
+}
+
+/// 2^{jB} for every chunk j, as the scalars of a multiexp
+fn chunk_scalars<F: PrimeField>() -> Vec<F::BigInt> {
+    powers(&F::from(1u64 << CHUNK_BITS), num_chunks::<F>())
+        .map(|p| p.into_repr())
+        .collect()
+}
+
+/// \sum_j 2^{jB} chunks_j
+fn recombine<F: PrimeField>(chunks: &[F]) -> F {
+    chunks
+        .iter()
+        .zip(powers(&F::from(1u64 << CHUNK_BITS), chunks.len()))
+        .map(|(c, p)| *c * p)
+        .sum()
+}
+
+/// 1, x, ..., x^{n-1}
+fn powers<F: Field>(x: &F, n: usize) -> impl Iterator<Item = F> + '_ {
+    std::iter::successors(Some(F::one()), move |p| Some(*p * x)).take(n)
+}
+
+/// Giant steps normalized at once when solving a discrete logarithm
+const GIANT_STEPS_BATCH: u64 = 1024;
+
+/// A baby-step giant-step table for discrete logarithms below `bound`
+struct BabySteps<G: AffineCurve> {
+    /// The baby steps [j] base, by a hash of the point to keep large tables small
+    table: HashMap<u64, u64>,
+    base: G,
+    giant_step: G::Projective,
+    steps: u64,
+}
+
+impl<G: AffineCurve> BabySteps<G> {
+    fn new(base: G, bound: u64) -> Self {
+        let steps = (bound as f64).sqrt().ceil() as u64;
+        let mut point = G::Projective::zero();
+        let mut baby_steps = Vec::with_capacity(steps as usize);
+        for _ in 0..steps {
+            baby_steps.push(point);
+            point += base.into_projective();
+        }
+        let mut table = HashMap::with_capacity(steps as usize);
+        for (j, p) in
+            G::Projective::batch_normalization_into_affine(&baby_steps)
+                .iter()
+                .enumerate()
+        {
+            table.entry(hash_point(p)).or_insert(j as u64);
+        }
+        Self {
+            table,
+            base,
+            giant_step: point,
+            steps,
+        }
+    }
+
+    fn discrete_log(&self, target: G::Projective) -> Option<u64> {
+        let mut point = target;
+        let mut i = 0;
+        while i < self.steps {
+            let batch = (self.steps - i).min(GIANT_STEPS_BATCH);
+            let mut giant_steps = Vec::with_capacity(batch as usize);
+            for _ in 0..batch {
+                giant_steps.push(point);
+                point -= self.giant_step;
+            }
+            let found =
+                G::Projective::batch_normalization_into_affine(&giant_steps)
+                    .iter()
+                    .zip(i..)
+                    .filter_map(|(p, i)| {
+                        self.table
+                            .get(&hash_point(p))
+                            .map(|j| i * self.steps + j)
+                    })
+                    // Rules out collisions of the hashes
+                    .find(|log| self.base.mul(*log) == target);
+            if found.is_some() {
+                return found;
+            }
+            i += batch;
+        }
+        None
+    }
+}
+
+fn hash_point<G: AffineCurve>(point: &G) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    point.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test_groth21 {
+    use super::*;
+    use crate::dkg::pv::test_common::*;
+    use ark_bls12_381::Bls12_381 as EllipticCurve;
+
+    type Fr = <EllipticCurve as PairingEngine>::Fr;
+    type G1 = <EllipticCurve as PairingEngine>::G1Affine;
+    type G2 = <EllipticCurve as PairingEngine>::G2Affine;
+
+    fn setup_groth21_dkg(validator: usize) -> Groth21Dkg<EllipticCurve> {
+        setup_scheme_dkg(2, 4, validator)
+    }
+
+    fn setup_dealt_groth21_dkg() -> Groth21Dkg<EllipticCurve> {
+        setup_dealt_scheme_dkg(2, 4)
+    }
+
+    /// Chunks that recombine to the share, with the first two out of range by `excess`
+    fn shifted_chunks(s: &Fr, excess: u64) -> Vec<Fr> {
+        let mut chunks = to_chunks(s);
+        chunks[0] += Fr::from(excess << CHUNK_BITS);
+        chunks[1] -= Fr::from(excess);
+        chunks
+    }
+
+    /// Chunks out of range, but within the bounds of the chunking proof
+    fn oversized_chunks(s: &Fr) -> Vec<Fr> {
+        shifted_chunks(s, 1 << 4)
+    }
+
+    #[test]
+    fn test_chunks_recombine() {
+        let rng = &mut ark_std::test_rng();
+        for s in [Fr::zero(), -Fr::one(), Fr::rand(rng)] {
+            let chunks = to_chunks(&s);
+            assert_eq!(chunks.len(), num_chunks::<Fr>());
+            assert!(chunks.iter().all(|c| *c < Fr::from(1u64 << CHUNK_BITS)));
+            assert_eq!(recombine(&chunks), s);
+            assert_eq!(recombine(&oversized_chunks(&s)), s);
+        }
+    }
+
+    /// Test the happy flow that a pvss with the correct form is created
+    /// and that appropriate validations pass
+    #[test]
+    fn test_new_groth21_pvss() {
+        let rng = &mut ark_std::test_rng();
+        let dkg = setup_groth21_dkg(0);
+        let s = Fr::rand(rng);
+        let pvss = Groth21Pvss::<EllipticCurve>::new(&s, &dkg, rng).unwrap();
+        assert_eq!(pvss.coeffs[0], G1::prime_subgroup_generator().mul(s));
+        assert_eq!(pvss.coeffs.len(), dkg.params.security_threshold as usize);
+        assert_eq!(pvss.shares.len(), dkg.validators.len());
+        assert!(pvss.verify_optimistic());
+        assert!(pvss.verify_full(&dkg));
+    }
+
+    /// Check that tampering with an encrypted chunk or the proofs fails full verification
+    #[test]
+    fn test_verify_groth21_pvss_bad_shares() {
+        let rng = &mut ark_std::test_rng();
+        let dkg = setup_groth21_dkg(0);
+        let pvss = Groth21Pvss::<EllipticCurve>::new(&Fr::rand(rng), &dkg, rng)
+            .unwrap();
+
+        let mut bad_pvss = pvss.clone();
+        bad_pvss.shares[0].chunks[3] = G2::zero();
+        assert!(bad_pvss.verify_optimistic());
+        assert!(!bad_pvss.verify_full(&dkg));
+
+        let mut bad_pvss = pvss.clone();
+        bad_pvss.sharing_proof.as_mut().unwrap().z_s += Fr::one();
+        assert!(!bad_pvss.verify_full(&dkg));
+
+        let mut bad_pvss = pvss.clone();
+        bad_pvss.chunking_proof.as_mut().unwrap().z_s[0] += 1;
+        assert!(!bad_pvss.verify_full(&dkg));
+
+        let mut bad_pvss = pvss.clone();
+        bad_pvss.chunking_proof.as_mut().unwrap().z_beta += Fr::one();
+        assert!(!bad_pvss.verify_full(&dkg));
+
+        // The chunking proof is bound to the chunks of its transcript
+        let other =
+            Groth21Pvss::<EllipticCurve>::new(&Fr::rand(rng), &dkg, rng)
+                .unwrap();
+        let mut bad_pvss = pvss;
+        bad_pvss.chunking_proof = other.chunking_proof;
+        assert!(!bad_pvss.verify_full(&dkg));
+    }
+
+    /// Aggregated transcripts give every validator a field element share of the final key
+    #[test]
+    fn test_aggregate_groth21_pvss() {
+        let keypairs = gen_keypairs();
+        let mut dkg = setup_dealt_groth21_dkg();
+        let aggregate = aggregate_groth21(&dkg).unwrap();
+        assert_eq!(aggregate.verify_aggregation(&dkg).unwrap(), 4);
+
+        let mut commitment = batch_to_projective(&aggregate.coeffs);
+        dkg.domain.fft_in_place(&mut commitment);
+        let shares = keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| {
+                let share = aggregate
+                    .decrypt_private_key_share(&dkg, &keypair.decryption_key, i)
+                    .unwrap();
+                assert_eq!(
+                    G1::prime_subgroup_generator().mul(share),
+                    commitment[i]
+                );
+                share
+            })
+            .collect::<Vec<_>>();
+
+        // Any threshold of shares interpolates to the secret of the final key
+        let threshold = dkg.params.security_threshold as usize;
+        let domain = (0..threshold)
+            .map(|i| dkg.domain.element(i))
+            .collect::<Vec<_>>();
+        let lagrange = group_threshold_cryptography::prepare_combine_simple::<
+            EllipticCurve,
+        >(&domain);
+        let secret = shares
+            .iter()
+            .zip(lagrange.iter())
+            .map(|(s_i, l_i)| *s_i * l_i)
+            .sum::<Fr>();
+        assert_eq!(G1::prime_subgroup_generator().mul(secret), dkg.final_key());
+
+        dkg.vss.remove(&3);
+        assert!(aggregate.verify_aggregation(&dkg).is_err());
+    }
+
+    /// Test that Groth21 transcripts go through the DKG messages
+    #[test]
+    fn test_groth21_dkg_aggregate() {
+        let mut dkg = setup_dealt_groth21_dkg();
+        let aggregate = dkg.aggregate().expect("Test failed");
+        let sender = dkg.validators[dkg.me].validator.clone();
+        assert!(dkg.verify_message(&sender, &aggregate).is_ok());
+        assert!(dkg.apply_message(sender, aggregate).is_ok());
+        assert!(matches!(dkg.state, DkgState::Success { .. }));
+    }
+
+    /// A dealer cannot prove chunks far out of range, while validators decrypt the
+    /// chunks the chunking proof allows out of range, alone or aggregated
+    #[test]
+    fn test_groth21_oversized_chunks() {
+        let rng = &mut ark_std::test_rng();
+        let keypairs = gen_keypairs();
+        let mut dkg = setup_dealt_groth21_dkg();
+        assert!(Groth21Pvss::new_with_chunking(
+            &Fr::rand(rng),
+            &setup_groth21_dkg(3),
+            rng,
+            |s| shifted_chunks(s, 1 << 24),
+        )
+        .is_err());
+
+        let bad_pvss = Groth21Pvss::new_with_chunking(
+            &Fr::rand(rng),
+            &setup_groth21_dkg(3),
+            rng,
+            oversized_chunks,
+        )
+        .unwrap();
+        assert!(bad_pvss.verify_optimistic());
+        assert!(bad_pvss.verify_full(&dkg));
+        dkg.vss.insert(3, bad_pvss.clone());
+        let aggregate = aggregate_groth21(&dkg).unwrap();
+        assert_eq!(aggregate.verify_aggregation(&dkg).unwrap(), 4);
+
+        let dk = &keypairs[1].decryption_key;
+        for coeffs in [&bad_pvss.coeffs, &aggregate.coeffs] {
+            let share = if coeffs == &bad_pvss.coeffs {
+                bad_pvss.decrypt_private_key_share(&dkg, dk, 1).unwrap()
+            } else {
+                aggregate.decrypt_private_key_share(&dkg, dk, 1).unwrap()
+            };
+            let mut commitment = batch_to_projective(coeffs);
+            dkg.domain.fft_in_place(&mut commitment);
+            assert_eq!(
+                G1::prime_subgroup_generator().mul(share),
+                commitment[1]
+            );
+        }
+    }
+}
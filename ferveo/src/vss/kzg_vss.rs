@@ -0,0 +1,322 @@
+use crate::*;
+use ark_ff::UniformRand;
+use ark_serialize::*;
+use ferveo_common::Keypair;
+use std::fmt;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Domain separation of the keys encrypting KZG VSS shares
+const KZG_SHARE_KEY_DST: &[u8] = b"FERVEO-V01-KZG-VSS-SHARE-KEY";
+
+/// The share of a KZG VSS dealing for one validator, S(ω_i) and its opening proof
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgShare<E: PairingEngine> {
+    pub evaluation: E::Fr,
+    pub proof: E::G1Affine,
+}
+
+impl<E: PairingEngine> fmt::Debug for KzgShare<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KzgShare")
+            .field("evaluation", &"<redacted>")
+            .field("proof", &self.proof)
+            .finish()
+    }
+}
+
+impl<E: PairingEngine> Drop for KzgShare<E> {
+    fn drop(&mut self) {
+        self.evaluation.zeroize();
+    }
+}
+
+/// A [`KzgShare`] encrypted to the session key ek_i of its validator
+pub type EncryptedKzgShare<E> = EncryptedShare<E>;
+
+/// A VSS dealing with a KZG commitment to the share polynomial S
+///
+/// Follows the dealer messages of the "Fast KZG DKG" chapter of the book: the commitment
+/// Ŝ, the secret commitment [S(0)] G and the proof π_0 that Ŝ - [S(0)] G opens to 0 at 0
+/// are public, while each validator receives S(ω_i) with its opening proof π_i, encrypted.
+/// Unlike Feldman commitments, every share proof is a single group element.
+///
+/// The KZG setup must stop at degree t - 1: without higher powers of τ, the dealer can not
+/// commit to a polynomial of higher degree, so any t shares determine S(0).
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgDealing<E: PairingEngine> {
+    pub tau: u64,
+    /// The index of the dealer among the validators
+    pub dealer: u32,
+    /// Ŝ
+    pub commitment: E::G1Affine,
+    /// [S(0)] G
+    pub secret_commitment: E::G1Affine,
+    /// π_0
+    pub proof_at_zero: E::G1Affine,
+    /// The shares of the validators, in the order of the DKG
+    pub shares: Vec<EncryptedKzgShare<E>>,
+}
+
+impl<E: PairingEngine> KzgDealing<E> {
+    /// Deal the secret `s` to the validators of `dkg`, as `dkg.me`
+    pub fn new<R: Rng>(
+        s: &E::Fr,
+        dkg: &PubliclyVerifiableDkg<E>,
+        kzg: &KzgParams<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        check_degree(dkg, kzg)?;
+        // S(x) = s + \sum_{i=1}^{t-1} a_i x^i
+        let mut phi = DensePolynomial::<E::Fr>::rand(
+            (dkg.params.security_threshold - 1) as usize,
+            rng,
+        );
+        phi.coeffs[0] = *s;
+
+        let commitment = kzg.commit(&phi)?;
+        let secret_commitment = kzg.g().mul(*s).into_affine();
+        let (_, proof_at_zero) = kzg.open(&phi, &E::Fr::zero())?;
+        let proofs = kzg.open_all(&phi, &dkg.domain)?;
+        let mut evals = phi.evaluate_over_domain_by_ref(dkg.domain);
+        phi.coeffs.zeroize();
+
+        let mut dealing = Self {
+            tau: dkg.params.tau,
+            dealer: dkg.me as u32,
+            commitment,
+            secret_commitment,
+            proof_at_zero,
+            shares: Vec::with_capacity(dkg.validators.len()),
+        };
+        let shares = dkg
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(i, val)| {
+                let share = KzgShare::<E> {
+                    evaluation: evals.evals[val.share_index],
+                    proof: proofs[val.share_index],
+                };
+                let ek_i = val.validator.public_key.encryption_key;
+                dealing.encrypt_share(i, &share, &ek_i, dkg, rng)
+            })
+            .collect::<Result<Vec<_>>>();
+        evals.evals.zeroize();
+        dealing.shares = shares?;
+        Ok(dealing)
+    }
+
+    /// Checks π_0, that Ŝ - [S(0)] G opens to 0 at 0, under a setup of degree t - 1
+    pub fn verify_public(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        kzg: &KzgParams<E>,
+    ) -> bool {
+        if check_degree(dkg, kzg).is_err() {
+            return false;
+        }
+        let commitment = (self.commitment.into_projective()
+            - self.secret_commitment.into_projective())
+        .into_affine();
+        kzg.verify(
+            &commitment,
+            &E::Fr::zero(),
+            &E::Fr::zero(),
+            &self.proof_at_zero,
+        )
+    }
+
+    /// Decrypts the share of `dkg.me` with its session `keypair`, and checks it opens Ŝ
+    /// at its domain point
+    ///
+    /// An error here is grounds for a dispute against the dealer.
+    pub fn decrypt_share(
+        &self,
+        dkg: &PubliclyVerifiableDkg<E>,
+        keypair: &Keypair<E>,
+        kzg: &KzgParams<E>,
+    ) -> Result<KzgShare<E>> {
+        if self.tau != dkg.params.tau {
+            return Err(anyhow!("dealing is for another DKG session"));
+        }
+        check_degree(dkg, kzg)?;
+        if !self.verify_public(dkg, kzg) {
+            return Err(anyhow!("invalid proof of the secret commitment"));
+        }
+        let validator = &dkg.validators[dkg.me];
+        if keypair.public() != validator.validator.public_key {
+            return Err(anyhow!(
+                "keypair is not the session keypair of this validator"
+            ));
+        }
+        let encrypted = self
+            .shares
+            .get(dkg.me)
+            .ok_or_else(|| anyhow!("no share for validator {}", dkg.me))?;
+
+        let plaintext = encrypted.decrypt(
+            &self.associated_data(dkg.me)?,
+            &keypair.decryption_key,
+            KZG_SHARE_KEY_DST,
+        )?;
+        let share = KzgShare::<E>::deserialize(&plaintext[..])?;
+
+        let point = dkg.domain.element(validator.share_index);
+        if !kzg.verify(
+            &self.commitment,
+            &point,
+            &share.evaluation,
+            &share.proof,
+        ) {
+            return Err(anyhow!("share does not open the commitment"));
+        }
+        Ok(share)
+    }
+
+    fn encrypt_share<R: Rng>(
+        &self,
+        index: usize,
+        share: &KzgShare<E>,
+        ek_i: &E::G2Affine,
+        dkg: &PubliclyVerifiableDkg<E>,
+        rng: &mut R,
+    ) -> Result<EncryptedKzgShare<E>> {
+        let mut plaintext = Zeroizing::new(vec![]);
+        share.serialize(&mut *plaintext)?;
+        EncryptedShare::encrypt(
+            &plaintext,
+            &self.associated_data(index)?,
+            ek_i,
+            &dkg.pvss_params.h.into_affine(),
+            KZG_SHARE_KEY_DST,
+            rng,
+        )
+    }
+
+    /// Binds an encrypted share to (τ, d, Ŝ) and its recipient
+    fn associated_data(&self, index: usize) -> Result<Vec<u8>> {
+        let mut aad = vec![];
+        CanonicalSerialize::serialize(
+            &(self.tau, self.dealer, index as u64),
+            &mut aad,
+        )?;
+        self.commitment.serialize(&mut aad)?;
+        Ok(aad)
+    }
+}
+
+/// Ŝ only binds the dealer to a polynomial of degree t - 1 if the setup stops there
+fn check_degree<E: PairingEngine>(
+    dkg: &PubliclyVerifiableDkg<E>,
+    kzg: &KzgParams<E>,
+) -> Result<()> {
+    let degree = (dkg.params.security_threshold - 1) as usize;
+    if kzg.max_degree() != degree {
+        return Err(anyhow!(
+            "KZG setup of degree {} does not match the threshold degree {}",
+            kzg.max_degree(),
+            degree
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_kzg_vss {
+    use super::*;
+    use crate::dkg::pv::test_common::*;
+    use ark_bls12_381::Bls12_381 as E;
+
+    type Fr = <E as PairingEngine>::Fr;
+
+    #[test]
+    fn test_kzg_dealing() {
+        let rng = &mut ark_std::test_rng();
+        let kzg = KzgParams::<E>::setup(1, rng);
+        let keypairs = gen_n_keypairs(4);
+        let s = Fr::rand(rng);
+        let dealing = KzgDealing::new(&s, &setup_dkg(0), &kzg, rng).unwrap();
+        assert_eq!(dealing.secret_commitment, kzg.g().mul(s).into_affine());
+        assert_eq!(dealing.shares.len(), 4);
+        assert!(dealing.verify_public(&setup_dkg(0), &kzg));
+
+        // Every validator recovers a share on S, which interpolates back to s
+        let shares = (0..4)
+            .map(|i| {
+                let dkg = setup_dkg(i);
+                let share =
+                    dealing.decrypt_share(&dkg, &keypairs[i], &kzg).unwrap();
+                assert!(dealing
+                    .decrypt_share(&dkg, &keypairs[(i + 1) % 4], &kzg)
+                    .is_err());
+                share.evaluation
+            })
+            .collect::<Vec<_>>();
+        let dkg = setup_dkg(0);
+        let domain = (0..4).map(|i| dkg.domain.element(i)).collect::<Vec<_>>();
+        let lagrange =
+            group_threshold_cryptography::prepare_combine_simple::<E>(&domain);
+        let secret = shares
+            .iter()
+            .zip(lagrange.iter())
+            .map(|(s_i, l_i)| *s_i * l_i)
+            .sum::<Fr>();
+        assert_eq!(secret, s);
+    }
+
+    #[test]
+    fn test_kzg_dealing_tampered() {
+        let rng = &mut ark_std::test_rng();
+        let kzg = KzgParams::<E>::setup(1, rng);
+        let keypairs = gen_n_keypairs(4);
+        let dkg = setup_dkg(1);
+        let dealing =
+            KzgDealing::new(&Fr::rand(rng), &setup_dkg(0), &kzg, rng).unwrap();
+
+        // A wrong secret commitment fails the public check
+        let mut bad = dealing.clone();
+        bad.secret_commitment = kzg.g();
+        assert!(!bad.verify_public(&dkg, &kzg));
+        assert!(bad.decrypt_share(&dkg, &keypairs[1], &kzg).is_err());
+
+        // A share swapped with another validator's does not decrypt
+        let mut bad = dealing.clone();
+        bad.shares.swap(1, 2);
+        assert!(bad.decrypt_share(&dkg, &keypairs[1], &kzg).is_err());
+
+        // A share that does not open the commitment is caught
+        let mut bad = dealing;
+        let share = KzgShare::<E> {
+            evaluation: Fr::rand(rng),
+            proof: kzg.g(),
+        };
+        let ek = keypairs[1].public().encryption_key;
+        bad.shares[1] = bad.encrypt_share(1, &share, &ek, &dkg, rng).unwrap();
+        assert!(bad.decrypt_share(&dkg, &keypairs[1], &kzg).is_err());
+    }
+
+    #[test]
+    fn test_kzg_dealing_rejects_setup_above_threshold_degree() {
+        let rng = &mut ark_std::test_rng();
+        let keypairs = gen_n_keypairs(4);
+        let dkg = setup_dkg(1);
+
+        // A dealer with a setup of degree 3 deals a polynomial of degree 3, so that
+        // the t = 2 shares of any two validators do not determine the secret
+        let high_kzg = KzgParams::<E>::setup(3, rng);
+        let high_dkg = setup_dkg_for_n_validators(4, 4, 0);
+        let dealing =
+            KzgDealing::new(&Fr::rand(rng), &high_dkg, &high_kzg, rng).unwrap();
+        assert!(dealing.verify_public(&high_dkg, &high_kzg));
+
+        // With t = 2, that setup is refused everywhere
+        assert!(
+            KzgDealing::new(&Fr::rand(rng), &setup_dkg(0), &high_kzg, rng)
+                .is_err()
+        );
+        assert!(!dealing.verify_public(&dkg, &high_kzg));
+        assert!(dealing
+            .decrypt_share(&dkg, &keypairs[1], &high_kzg)
+            .is_err());
+    }
+}
@@ -9,11 +9,14 @@ use ark_ff::UniformRand;
 use ark_serialize::*;
 use ferveo_common::{Keypair, PublicKey};
 use group_threshold_cryptography::{
+    check_ciphertext_validity, prepare_combine_simple,
     refresh_private_key_share, update_share_for_recovery, Ciphertext,
-    DecryptionShareFast, DecryptionShareSimple, PrivateKeyShare,
+    DecryptionShareFast, DecryptionShareSimple,
+    DecryptionShareSimplePrecomputed, HashToCurve, PrivateKeyShare,
 };
 use itertools::{zip_eq, Itertools};
 use subproductdomain::fast_multiexp;
+use zeroize::Zeroize;
 
 /// These are the blinded evaluations of shares of a single random polynomial
 pub type ShareEncryptions<E> = <E as PairingEngine>::G2Affine;
@@ -88,12 +91,10 @@ impl<E: PairingEngine, T> PubliclyVerifiableSS<E, T> {
         phi.coeffs[0] = *s; // setting the first coefficient to secret value
 
         // Evaluations of the polynomial over the domain
-        let evals = phi.evaluate_over_domain_by_ref(dkg.domain);
+        let mut evals = phi.evaluate_over_domain_by_ref(dkg.domain);
         // commitment to coeffs, F_i
         let coeffs = fast_multiexp(&phi.coeffs, dkg.pvss_params.g);
-        let shares = dkg
-            .validators
-            .iter()
+        let shares = cfg_iter!(dkg.validators)
             .map(|val| {
                 // ek_{i}^{eval_i}, i = validator index
                 fast_multiexp(
@@ -103,12 +104,13 @@ impl<E: PairingEngine, T> PubliclyVerifiableSS<E, T> {
                 )[0]
             })
             .collect::<Vec<ShareEncryptions<E>>>();
+        phi.coeffs.zeroize();
+        evals.evals.zeroize();
         if shares.len() != dkg.validators.len() {
             return Err(anyhow!(
                 "Not all validator session keys have been announced"
             ));
         }
-        // phi.zeroize(); // TODO zeroize?
         // TODO: Cross check proof of knowledge check with the whitepaper; this check proves that there is a relationship between the secret and the pvss transcript
         // Sigma is a proof of knowledge of the secret, sigma = h^s
         let sigma = E::G2Affine::prime_subgroup_generator().mul(*s).into(); //todo hash to curve
@@ -150,10 +152,8 @@ impl<E: PairingEngine, T> PubliclyVerifiableSS<E, T> {
         dkg.domain.fft_in_place(&mut commitment);
 
         // Each validator checks that their share is correct
-        dkg.validators
-            .iter()
-            .zip(self.shares.iter())
-            .all(|(validator, y_i)| {
+        cfg_iter!(dkg.validators).zip(cfg_iter!(self.shares)).all(
+            |(validator, y_i)| {
                 // TODO: Check #3 is missing
                 // See #3 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf
 
@@ -168,7 +168,8 @@ impl<E: PairingEngine, T> PubliclyVerifiableSS<E, T> {
                 // See #4 in 4.2.3 section of https://eprint.iacr.org/2022/898.pdf
                 // e(G,Y) = e(A, ek)
                 E::pairing(dkg.pvss_params.g, *y_i) == E::pairing(a_i, ek_i)
-            })
+            },
+        )
     }
 }
 
@@ -217,6 +218,47 @@ impl<E: PairingEngine, T: Aggregate> PubliclyVerifiableSS<E, T> {
         PrivateKeyShare { private_key_share }
     }
 
+    pub fn update_private_key_share_for_recovery(
+        &self,
+        validator_decryption_key: &E::Fr,
+        validator_index: usize,
+        share_updates: &[E::G2Projective],
+    ) -> PrivateKeyShare<E> {
+        // Retrieves their private key share
+        let private_key_share = self.decrypt_private_key_share(
+            validator_decryption_key,
+            validator_index,
+        );
+
+        // And updates their share
+        update_share_for_recovery::<E>(&private_key_share, share_updates)
+    }
+}
+
+impl<E: HashToCurve, T: Aggregate> PubliclyVerifiableSS<E, T> {
+    /// Creates the fast-variant decryption share [dk_i^-1] U of validator `validator_index`
+    ///
+    /// The aggregated share Y_i is the blinded key share [b] Z_i with b = dk_i, so the
+    /// share is combined with the contexts of [`DkgOutput::public_decryption_contexts_fast`].
+    pub fn make_decryption_share_fast(
+        &self,
+        ciphertext: &Ciphertext<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::Fr,
+        validator_index: usize,
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionShareFast<E>> {
+        check_ciphertext_validity(ciphertext, aad, g_inv)?;
+        let decryption_share = ciphertext
+            .commitment
+            .mul(validator_decryption_key.inverse().unwrap().into_repr())
+            .into_affine();
+        Ok(DecryptionShareFast {
+            decrypter_index: validator_index,
+            decryption_share,
+        })
+    }
+
     pub fn make_decryption_share_simple(
         &self,
         ciphertext: &Ciphertext<E>,
@@ -240,6 +282,51 @@ impl<E: PairingEngine, T: Aggregate> PubliclyVerifiableSS<E, T> {
         .unwrap() // TODO: Add proper error handling
     }
 
+    /// Creates the precomputed decryption share of validator `validator_index` for a
+    /// `committee` of validators, indices into the validators of `dkg`, decided ahead of time
+    ///
+    /// Combined with [`group_threshold_cryptography::checked_share_combine_simple_precomputed`].
+    pub fn make_decryption_share_simple_precomputed(
+        &self,
+        ciphertext: &Ciphertext<E>,
+        aad: &[u8],
+        validator_decryption_key: &E::Fr,
+        validator_index: usize,
+        committee: &[usize],
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<DecryptionShareSimplePrecomputed<E>> {
+        let position = committee
+            .iter()
+            .position(|i| *i == validator_index)
+            .ok_or_else(|| {
+                anyhow!("validator {} is not in the committee", validator_index)
+            })?;
+        let domain = committee
+            .iter()
+            .map(|i| {
+                dkg.validators
+                    .get(*i)
+                    .map(|v| dkg.domain.element(v.share_index))
+                    .ok_or_else(|| anyhow!("unknown validator {}", i))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let lagrange_coeffs = prepare_combine_simple::<E>(&domain);
+
+        let private_key_share = self.decrypt_private_key_share(
+            validator_decryption_key,
+            validator_index,
+        );
+        Ok(DecryptionShareSimplePrecomputed::create(
+            validator_index,
+            validator_decryption_key,
+            &private_key_share,
+            ciphertext,
+            aad,
+            &lagrange_coeffs[position],
+            &dkg.pvss_params.g_inv(),
+        )?)
+    }
+
     pub fn refresh_decryption_share(
         &self,
         ciphertext: &Ciphertext<E>,
@@ -272,22 +359,6 @@ impl<E: PairingEngine, T: Aggregate> PubliclyVerifiableSS<E, T> {
         )
         .unwrap() // TODO: Add proper error handling
     }
-
-    pub fn update_private_key_share_for_recovery(
-        &self,
-        validator_decryption_key: &E::Fr,
-        validator_index: usize,
-        share_updates: &[E::G2Projective],
-    ) -> PrivateKeyShare<E> {
-        // Retrieves their private key share
-        let private_key_share = self.decrypt_private_key_share(
-            validator_decryption_key,
-            validator_index,
-        );
-
-        // And updates their share
-        update_share_for_recovery::<E>(&private_key_share, share_updates)
-    }
 }
 
 /// Aggregate the PVSS instances in `pvss` from DKG session `dkg`
@@ -309,13 +380,11 @@ pub fn aggregate<E: PairingEngine>(
     // Aggregating is just adding the corresponding values in pvss instances, so pvss = pvss + pvss_j
     for (_, next) in pvss_iter {
         sigma = sigma.add(next.sigma);
-        coeffs
-            .iter_mut()
-            .zip_eq(next.coeffs.iter())
+        cfg_iter_mut!(coeffs)
+            .zip_eq(cfg_iter!(next.coeffs))
             .for_each(|(a, b)| *a += b.into_projective());
-        shares
-            .iter_mut()
-            .zip_eq(next.shares.iter())
+        cfg_iter_mut!(shares)
+            .zip_eq(cfg_iter!(next.shares))
             .for_each(|(a, b)| *a += b.into_projective());
     }
     let shares = E::G2Projective::batch_normalization_into_affine(&shares);
@@ -328,6 +397,41 @@ pub fn aggregate<E: PairingEngine>(
     }
 }
 
+impl<E: PairingEngine> PvssScheme<E> for PubliclyVerifiableSS<E> {
+    type Aggregated = AggregatedPvss<E>;
+
+    fn deal<R: Rng>(
+        s: &E::Fr,
+        dkg: &PubliclyVerifiableDkg<E>,
+        rng: &mut R,
+    ) -> Result<Self> {
+        Self::new(s, dkg, rng)
+    }
+
+    fn secret_commitment(&self) -> E::G1Affine {
+        self.coeffs[0]
+    }
+
+    fn verify_optimistic(&self) -> bool {
+        PubliclyVerifiableSS::verify_optimistic(self)
+    }
+
+    fn verify_full(&self, dkg: &PubliclyVerifiableDkg<E>) -> bool {
+        PubliclyVerifiableSS::verify_full(self, dkg)
+    }
+
+    fn aggregate(dkg: &PubliclyVerifiableDkg<E>) -> Result<AggregatedPvss<E>> {
+        Ok(aggregate(dkg))
+    }
+
+    fn verify_aggregation(
+        aggregated: &AggregatedPvss<E>,
+        dkg: &PubliclyVerifiableDkg<E>,
+    ) -> Result<u32> {
+        aggregated.verify_aggregation(dkg)
+    }
+}
+
 #[cfg(test)]
 mod test_pvss {
     use super::*;
@@ -1,10 +1,18 @@
 #![allow(non_snake_case)]
 #![allow(dead_code)]
 
-use ark_ec::{msm::FixedBaseMSM, PairingEngine, ProjectiveCurve};
+use ark_ec::{
+    msm::{FixedBaseMSM, VariableBaseMSM},
+    AffineCurve, PairingEngine, ProjectiveCurve,
+};
 use ark_ff::{FftField, Field, PrimeField, Zero};
 use ark_poly::polynomial::univariate::DensePolynomial as Poly;
-use ark_poly::{Polynomial, UVPolynomial};
+use ark_poly::{
+    EvaluationDomain, Polynomial, Radix2EvaluationDomain, UVPolynomial,
+};
+use ark_std::{cfg_iter, cfg_iter_mut};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Compute a fast multiexp of many scalars times the same base
 /// Only convenient for when called once with given base; if called
@@ -40,9 +48,9 @@ pub fn moduli_from_scalar<F: FftField>(s: &F) -> Poly<F> {
     }
 }
 
-/// Where indicated, algorithms are from Modern Computer Algebra, 3rd edition, by Gathen and Gerhard
-/// Abbreviated as GG
-/// Let M(n) denote the time to multiply.
+// Where indicated, algorithms are from Modern Computer Algebra, 3rd edition, by Gathen and Gerhard
+// Abbreviated as GG
+// Let M(n) denote the time to multiply.
 
 /// GG Algorithm 9.3
 /// Computes the inverse of f mod x^l
@@ -163,6 +171,16 @@ impl<F: FftField> SubproductDomain<F> {
         self.t.inverse_lagrange_coefficients(&self.u)
     }
 
+    /// Compute the lagrange coefficients L_i(x) over the domain u
+    /// Takes O(M(n) log n) field operations and a batch inversion
+    pub fn lagrange_coefficients_at(&self, x: &F) -> Vec<F> {
+        lagrange_coefficients_from_prime(
+            &self.u,
+            x,
+            self.inverse_lagrange_coefficients(),
+        )
+    }
+
     #[allow(dead_code)]
     /// Compute a linear combination of lagrange factors times c_i
     pub fn linear_combine(&self, c: &[F]) -> Poly<F> {
@@ -170,6 +188,108 @@ impl<F: FftField> SubproductDomain<F> {
     }
 }
 
+/// Below this many points, m'(u_i) is computed as a direct product instead of over a
+/// subproduct tree, whose polynomial arithmetic only pays off for very large domains
+///
+/// Measured crossover of the `lagrange_denominators_*` benchmarks in tpke on BLS12-381,
+/// single-threaded.
+pub const SUBPRODUCT_THRESHOLD: usize = 6400;
+
+/// Compute the lagrange coefficients L_i(x) over the points u
+/// L_i(x) = m(x) / ((x - u_i) * m'(u_i)), with the denominators inverted in a batch
+pub fn lagrange_coefficients_at<F: FftField>(u: &[F], x: &F) -> Vec<F> {
+    if u.len() < SUBPRODUCT_THRESHOLD {
+        let prime = cfg_iter!(u)
+            .map(|u_i| {
+                u.iter()
+                    .filter(|u_j| *u_j != u_i)
+                    .map(|u_j| *u_i - u_j)
+                    .product()
+            })
+            .collect();
+        lagrange_coefficients_from_prime(u, x, prime)
+    } else {
+        SubproductDomain::new(u.to_vec()).lagrange_coefficients_at(x)
+    }
+}
+
+/// L_i(x) from the evaluations m'(u_i) of the derivative of m
+fn lagrange_coefficients_from_prime<F: FftField>(
+    u: &[F],
+    x: &F,
+    mut prime: Vec<F>,
+) -> Vec<F> {
+    if let Some(i) = u.iter().position(|u_i| u_i == x) {
+        // Every other lagrange polynomial vanishes at u_i
+        let mut lagrange = vec![F::zero(); u.len()];
+        lagrange[i] = F::one();
+        return lagrange;
+    }
+    let m_x = u.iter().map(|u_i| *x - u_i).product::<F>();
+    cfg_iter_mut!(prime)
+        .zip(cfg_iter!(u))
+        .for_each(|(l_i, u_i)| *l_i *= *x - u_i);
+    ark_ff::batch_inversion_and_mul(&mut prime, &m_x);
+    prime
+}
+
+/// Interpolation and evaluation in the exponent, with values [v_i] G for a group generator G
+/// Group polynomials are multiplied by the scalar polynomials of the subproduct tree, so
+/// the scalar field must be the prime field of the curve
+impl<F: PrimeField> SubproductDomain<F> {
+    /// Interpolate f such that [f(u_i)] G = v_i, and output [f(x)] G
+    /// Takes one multiexp of size n
+    pub fn interpolate_at_in_exponent<G: AffineCurve<ScalarField = F>>(
+        &self,
+        v: &[G],
+        x: &F,
+    ) -> G {
+        assert_eq!(v.len(), self.u.len());
+        let scalars = self
+            .lagrange_coefficients_at(x)
+            .iter()
+            .map(|l_i| l_i.into_repr())
+            .collect::<Vec<_>>();
+        VariableBaseMSM::multi_scalar_mul(v, &scalars).into_affine()
+    }
+
+    /// Interpolate f such that [f(u_i)] G = v_i, and output the coefficients [f_k] G
+    /// Takes O(M(n) log n) group operations, like [`SubproductDomain::interpolate`]
+    pub fn interpolate_in_exponent<G: AffineCurve<ScalarField = F>>(
+        &self,
+        v: &[G],
+    ) -> Vec<G> {
+        assert_eq!(v.len(), self.u.len());
+        let mut prime_inv = self.inverse_lagrange_coefficients();
+        ark_ff::batch_inversion(&mut prime_inv);
+        let c = cfg_iter!(v)
+            .zip(cfg_iter!(prime_inv))
+            .map(|(v_i, prime_inv_i)| v_i.mul(*prime_inv_i))
+            .collect::<Vec<_>>();
+        let coeffs = self.t.linear_combine_in_exponent(&self.u, &c);
+        G::Projective::batch_normalization_into_affine(&coeffs)
+    }
+
+    /// Evaluate f over the domain u from its coefficients [f_k] G, outputting [f(u_i)] G
+    /// Takes O(M(n) log n) group operations, like [`SubproductDomain::evaluate`]
+    pub fn evaluate_in_exponent<G: AffineCurve<ScalarField = F>>(
+        &self,
+        f: &[G],
+    ) -> Vec<G> {
+        let mut f = f
+            .iter()
+            .map(|f_k| f_k.into_projective())
+            .collect::<Vec<_>>();
+        if self.u.len() < f.len() {
+            // f and its remainder mod m agree on the domain
+            f = remainder_in_exponent(&f, &self.t.m);
+        }
+        let mut evals = vec![G::Projective::zero(); self.u.len()];
+        self.t.evaluate_in_exponent(&f, &self.u, &mut evals);
+        G::Projective::batch_normalization_into_affine(&evals)
+    }
+}
+
 /// A subproduct tree of the subproduct domain
 /// This type is defined separately from SubproductDomain
 /// because the domain u is owned by SubproductDomain, whereas
@@ -299,6 +419,138 @@ impl<F: FftField> SubproductTree<F> {
     }
 }
 
+/// Operations over the subproduct tree on polynomials with coefficients [f_k] G
+impl<F: PrimeField> SubproductTree<F> {
+    /// Like [`SubproductTree::evaluate`], for f with coefficients [f_k] G
+    /// The output is stored in the slice t:
+    /// t_i = [f(u_i)] G
+    pub fn evaluate_in_exponent<G: ProjectiveCurve<ScalarField = F>>(
+        &self,
+        f: &[G],
+        u: &[F],
+        t: &mut [G],
+    ) {
+        assert!(f.len() <= u.len());
+
+        if u.len() == 1 {
+            // By the assertion above, f is a constant, or zero if it has no coefficients
+            t[0] = f.first().copied().unwrap_or_else(G::zero);
+            return;
+        }
+
+        let left = self.left.as_ref().unwrap();
+        let right = self.right.as_ref().unwrap();
+
+        let r_0 = remainder_in_exponent(f, &left.m);
+        let r_1 = remainder_in_exponent(f, &right.m);
+
+        let n = u.len() / 2;
+        let (u_0, u_1) = u.split_at(n);
+        let (t_0, t_1) = t.split_at_mut(n);
+
+        left.evaluate_in_exponent(&r_0, u_0, t_0);
+        right.evaluate_in_exponent(&r_1, u_1, t_1);
+    }
+
+    /// Like [`SubproductTree::linear_combine`], for c_i = [c_i] G
+    /// Outputs the coefficients of sum_i (c_i * m) / (x - u_i) in the exponent
+    pub fn linear_combine_in_exponent<G: ProjectiveCurve<ScalarField = F>>(
+        &self,
+        u: &[F],
+        c: &[G],
+    ) -> Vec<G> {
+        if u.len() == 1 {
+            return vec![c[0]];
+        }
+        let n = u.len() / 2;
+        let (u_0, u_1) = u.split_at(n);
+        let (c_0, c_1) = c.split_at(n);
+
+        let left = self.left.as_ref().unwrap();
+        let right = self.right.as_ref().unwrap();
+        let r_0 = left.linear_combine_in_exponent(u_0, c_0);
+        let r_1 = right.linear_combine_in_exponent(u_1, c_1);
+
+        // Both products have u.len() coefficients
+        mul_in_exponent(&r_0, &right.m)
+            .iter()
+            .zip(mul_in_exponent(&r_1, &left.m))
+            .map(|(a, b)| *a + b)
+            .collect()
+    }
+}
+
+/// Multiply f, with coefficients [f_k] G, by the polynomial g
+/// Takes O(M(n)) group operations with FFTs over the group, or a schoolbook product when
+/// that needs fewer scalar multiplications
+pub fn mul_in_exponent<G: ProjectiveCurve>(
+    f: &[G],
+    g: &Poly<G::ScalarField>,
+) -> Vec<G> {
+    if f.is_empty() || g.is_zero() {
+        return vec![];
+    }
+    let len = f.len() + g.coeffs.len() - 1;
+    let domain = Radix2EvaluationDomain::<G::ScalarField>::new(len)
+        .expect("product too large for an FFT domain");
+    let log_size = ark_std::log2(domain.size()) as usize;
+
+    if f.len() * g.coeffs.len() <= domain.size() * (log_size + 1) {
+        let mut product = vec![G::zero(); len];
+        for (i, f_i) in f.iter().enumerate() {
+            for (j, g_j) in g.coeffs.iter().enumerate() {
+                product[i + j] += f_i.mul(g_j.into_repr());
+            }
+        }
+        return product;
+    }
+
+    let mut product = f.to_vec();
+    domain.fft_in_place(&mut product);
+    let g_evals = domain.fft(&g.coeffs);
+    cfg_iter_mut!(product)
+        .zip(cfg_iter!(g_evals))
+        .for_each(|(p_i, g_i)| *p_i *= *g_i);
+    domain.ifft_in_place(&mut product);
+    product.truncate(len);
+    product
+}
+
+/// GG Algorithm 9.5 in the exponent
+/// The remainder of f, with coefficients [f_k] G, divided by the monic polynomial m
+pub fn remainder_in_exponent<G: ProjectiveCurve>(
+    f: &[G],
+    m: &Poly<G::ScalarField>,
+) -> Vec<G> {
+    let deg_m = m.coeffs.len() - 1;
+    if f.len() <= deg_m {
+        return f.to_vec();
+    }
+    let deg_diff = f.len() - m.coeffs.len();
+
+    // rev(q) = rev(f) / rev(m) mod x^{deg_diff + 1}
+    let mut rev_m = m.clone();
+    rev_m.reverse();
+    let mut rev_m_inv = inverse_mod_xl(&rev_m, deg_diff + 1).unwrap();
+    rev_m_inv.coeffs.truncate(deg_diff + 1);
+    let rev_f = f
+        .iter()
+        .rev()
+        .take(deg_diff + 1)
+        .copied()
+        .collect::<Vec<_>>();
+    let mut quotient = mul_in_exponent(&rev_f, &rev_m_inv);
+    quotient.resize(deg_diff + 1, G::zero());
+    quotient.reverse();
+
+    // Only the coefficients below deg(m) are left in f - m * q
+    f.iter()
+        .zip(mul_in_exponent(&quotient, m))
+        .take(deg_m)
+        .map(|(f_k, mq_k)| *f_k - mq_k)
+        .collect()
+}
+
 /// Compute the derivative of polynomial f
 pub fn derivative<F: FftField>(f: &Poly<F>) -> Poly<F> {
     let mut coeffs = Vec::with_capacity(f.coeffs().len() - 1);
@@ -472,6 +724,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_interpolate_in_exponent() {
+        type G1 = <ark_bls12_381::Bls12_381 as PairingEngine>::G1Affine;
+        let rng = &mut ark_std::test_rng();
+        let g = G1::prime_subgroup_generator();
+        // Large enough domains for group FFTs in the subproduct tree
+        for d in (1..20).chain([70]) {
+            let u = (0..d).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            let f = DensePolynomial::<Fr>::rand(d - 1, rng);
+            let evals = u
+                .iter()
+                .map(|u_i| g.mul(f.evaluate(u_i)).into_affine())
+                .collect::<Vec<_>>();
+            let s = SubproductDomain::<Fr>::new(u.clone());
+
+            // Naive lagrange interpolation at x
+            let x = Fr::rand(rng);
+            let mut naive = G1::zero().into_projective();
+            for (i, u_i) in u.iter().enumerate() {
+                let mut l_i = Fr::one();
+                for (j, u_j) in u.iter().enumerate() {
+                    if i != j {
+                        l_i *= (x - u_j) / (*u_i - u_j);
+                    }
+                }
+                naive += evals[i].mul(l_i);
+            }
+            assert_eq!(s.interpolate_at_in_exponent(&evals, &x), naive);
+            assert_eq!(
+                s.interpolate_at_in_exponent(&evals, &u[d - 1]),
+                evals[d - 1]
+            );
+
+            let coeffs = s.interpolate_in_exponent(&evals);
+            assert_eq!(coeffs.len(), d);
+            for (c, f_k) in coeffs.iter().zip(f.coeffs.iter()) {
+                assert_eq!(*c, g.mul(*f_k).into_affine());
+            }
+            assert_eq!(s.evaluate_in_exponent(&coeffs), evals);
+
+            // Polynomials of higher degree than the domain are reduced first
+            let f = DensePolynomial::<Fr>::rand(d + 3, rng);
+            let coeffs = f
+                .coeffs
+                .iter()
+                .map(|f_k| g.mul(*f_k).into_affine())
+                .collect::<Vec<_>>();
+            let evals = u
+                .iter()
+                .map(|u_i| g.mul(f.evaluate(u_i)).into_affine())
+                .collect::<Vec<_>>();
+            assert_eq!(s.evaluate_in_exponent(&coeffs), evals);
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients_at() {
+        let rng = &mut ark_std::test_rng();
+        for d in [1, 2, 7, 100] {
+            let u = (0..d).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            let x = Fr::rand(rng);
+            // Direct products agree with the subproduct tree
+            let s = SubproductDomain::<Fr>::new(u.clone());
+            assert_eq!(
+                lagrange_coefficients_at(&u, &x),
+                s.lagrange_coefficients_at(&x)
+            );
+
+            let mut expected = vec![Fr::zero(); d];
+            expected[d - 1] = Fr::one();
+            assert_eq!(lagrange_coefficients_at(&u, &u[d - 1]), expected);
+        }
+        assert!(lagrange_coefficients_at::<Fr>(&[], &Fr::one()).is_empty());
+    }
+
     #[test]
     fn test_inv_lagrange() {
         let rng = &mut ark_std::test_rng();
@@ -250,6 +250,40 @@ pub fn encrypt(
     }
 }
 
+/// A public key prepared once for encrypting many messages to it
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct PreparedPublicKey(tpke::api::PreparedPublicKey);
+
+#[wasm_bindgen]
+impl PreparedPublicKey {
+    #[wasm_bindgen(constructor)]
+    pub fn new(public_key: &PublicKey) -> Self {
+        set_panic_hook();
+        Self(tpke::api::PreparedPublicKey::new(&public_key.0))
+    }
+
+    #[wasm_bindgen]
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.public_key())
+    }
+
+    #[wasm_bindgen]
+    pub fn encrypt(&self, message: &[u8], aad: &[u8]) -> Ciphertext {
+        set_panic_hook();
+
+        let mut rng = rand::thread_rng();
+        let g_inv = TpkeG1Prepared::from(-G1Affine::prime_subgroup_generator());
+        let ciphertext = self.0.encrypt(message, aad, &mut rng);
+
+        Ciphertext {
+            ciphertext,
+            aad: aad.to_vec(),
+            g_inv,
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub fn decrypt(ciphertext: &Ciphertext, private_key: &PrivateKey) -> Vec<u8> {
     set_panic_hook();
@@ -45,6 +45,28 @@ fn encrypts_and_decrypts() {
     assert_eq!(message, plaintext[..message.len()])
 }
 
+#[test]
+#[wasm_bindgen_test]
+fn encrypts_to_prepared_public_key() {
+    let threshold = 3;
+    let shares_num = 5;
+    let aad = "my-aad".as_bytes().to_vec();
+
+    let setup = Setup::new(threshold, shares_num);
+    let prepared_key = PreparedPublicKey::new(&setup.public_key);
+    assert_eq!(
+        prepared_key.public_key().to_bytes(),
+        setup.public_key.to_bytes()
+    );
+
+    for message in ["first-message", "second-message"] {
+        let message = message.as_bytes().to_vec();
+        let ciphertext = prepared_key.encrypt(&message, &aad);
+        let plaintext = decrypt(&ciphertext, &setup.private_key);
+        assert_eq!(message, plaintext[..message.len()])
+    }
+}
+
 #[test]
 #[wasm_bindgen_test]
 fn threshold_encryption() {
@@ -2,8 +2,9 @@
 
 use ark_bls12_381::{Fr, G1Affine, G2Affine};
 use ark_ec::AffineCurve;
-use ark_ff::Zero;
+use ark_ff::{UniformRand, Zero};
 use std::collections::HashMap;
+use subproductdomain::SubproductDomain;
 
 use criterion::{
     black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
@@ -15,6 +16,7 @@ use rand_core::{RngCore, SeedableRng};
 
 const NUM_SHARES_CASES: [usize; 5] = [4, 8, 16, 32, 64];
 const MSG_SIZE_CASES: [usize; 7] = [256, 512, 1024, 2048, 4096, 8192, 16384];
+const NUM_CIPHERTEXTS_CASES: [usize; 4] = [1, 16, 64, 256];
 
 type E = ark_bls12_381::Bls12_381;
 type G2Prepared = ark_ec::bls12::G2Prepared<ark_bls12_381::Parameters>;
@@ -118,12 +120,12 @@ impl SetupSimple {
             .map(|context| context.create_share(&ciphertext, aad).unwrap())
             .collect();
 
-        let pub_contexts = contexts[0].clone().public_decryption_contexts;
+        let pub_contexts = contexts[0].public_decryption_contexts.clone();
         let domain: Vec<Fr> = pub_contexts.iter().map(|c| c.domain).collect();
         let lagrange = prepare_combine_simple::<E>(&domain);
 
         let shared_secret =
-            share_combine_simple::<E>(&decryption_shares, &lagrange);
+            share_combine_simple::<E>(&decryption_shares, &lagrange).unwrap();
 
         let shared = SetupShared {
             threshold,
@@ -202,10 +204,13 @@ pub fn bench_create_decryption_share(c: &mut Criterion) {
                         .iter()
                         .zip_eq(setup.lagrange_coeffs.iter())
                         .map(|(context, lagrange_coeff)| {
-                            context.create_share_precomputed(
-                                &setup.shared.ciphertext,
-                                lagrange_coeff,
-                            )
+                            context
+                                .create_share_precomputed(
+                                    &setup.shared.ciphertext,
+                                    &setup.shared.aad,
+                                    lagrange_coeff,
+                                )
+                                .unwrap()
                         })
                         .collect::<Vec<_>>(),
                 );
@@ -227,6 +232,44 @@ pub fn bench_create_decryption_share(c: &mut Criterion) {
     }
 }
 
+/// Run with `--features parallel` to compare against the single-threaded version
+pub fn bench_create_share_bundle(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+
+    let mut group = c.benchmark_group("SHARE BUNDLE CREATE");
+    group.sample_size(10);
+
+    let shares_num = NUM_SHARES_CASES[0];
+    let setup = SetupFast::new(shares_num, MSG_SIZE_CASES[0], rng);
+    let aad = setup.shared.aad.as_slice();
+
+    for ciphertexts_num in NUM_CIPHERTEXTS_CASES {
+        let ciphertexts = (0..ciphertexts_num)
+            .map(|_| {
+                encrypt::<_, E>(
+                    &setup.shared.msg,
+                    aad,
+                    &setup.shared.pubkey,
+                    rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        let aads = vec![aad; ciphertexts_num];
+
+        group.bench_function(
+            BenchmarkId::new("share_bundle_create_fast", ciphertexts_num),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        setup.contexts[0]
+                            .create_share_bundle(&ciphertexts, &aads),
+                    )
+                })
+            },
+        );
+    }
+}
+
 pub fn bench_share_prepare(c: &mut Criterion) {
     let rng = &mut StdRng::seed_from_u64(0);
 
@@ -262,6 +305,57 @@ pub fn bench_share_prepare(c: &mut Criterion) {
     }
 }
 
+pub fn bench_lagrange_coeffs(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(0);
+
+    let mut group = c.benchmark_group("LAGRANGE COEFFS");
+    group.sample_size(10);
+
+    for shares_num in [64, 256, 1024, 4096] {
+        let domain = (0..shares_num).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        group.bench_function(
+            BenchmarkId::new("prepare_combine_simple", shares_num),
+            |b| b.iter(|| black_box(prepare_combine_simple::<E>(&domain))),
+        );
+    }
+
+    // Both ways of computing the denominators Z'(x_j) of the Lagrange coefficients,
+    // around the committee size where `prepare_combine_simple` switches between them
+    for shares_num in [1024, 4096, 6144, 7168, 8192] {
+        let domain = (0..shares_num).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        group.bench_function(
+            BenchmarkId::new("lagrange_denominators_direct", shares_num),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        domain
+                            .iter()
+                            .map(|x_j| {
+                                domain
+                                    .iter()
+                                    .filter(|x_m| *x_m != x_j)
+                                    .map(|x_m| *x_j - x_m)
+                                    .product::<Fr>()
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("lagrange_denominators_subproduct", shares_num),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        SubproductDomain::new(domain.clone())
+                            .inverse_lagrange_coefficients(),
+                    )
+                })
+            },
+        );
+    }
+}
+
 pub fn bench_share_combine(c: &mut Criterion) {
     let rng = &mut StdRng::seed_from_u64(0);
 
@@ -283,10 +377,13 @@ pub fn bench_share_combine(c: &mut Criterion) {
         let simple = {
             let setup = SetupSimple::new(shares_num, msg_size, rng);
             move || {
-                black_box(share_combine_simple::<E>(
-                    &setup.decryption_shares,
-                    &setup.lagrange_coeffs,
-                ));
+                black_box(
+                    share_combine_simple::<E>(
+                        &setup.decryption_shares,
+                        &setup.lagrange_coeffs,
+                    )
+                    .unwrap(),
+                );
             }
         };
         let simple_precomputed = {
@@ -297,10 +394,13 @@ pub fn bench_share_combine(c: &mut Criterion) {
                 .iter()
                 .zip_eq(setup.lagrange_coeffs.iter())
                 .map(|(context, lagrange_coeff)| {
-                    context.create_share_precomputed(
-                        &setup.shared.ciphertext,
-                        lagrange_coeff,
-                    )
+                    context
+                        .create_share_precomputed(
+                            &setup.shared.ciphertext,
+                            &setup.shared.aad,
+                            lagrange_coeff,
+                        )
+                        .unwrap()
                 })
                 .collect();
 
@@ -326,6 +426,65 @@ pub fn bench_share_combine(c: &mut Criterion) {
     }
 }
 
+pub fn bench_share_combine_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("SHARE COMBINE BATCH");
+    group.sample_size(10);
+
+    let rng = &mut StdRng::seed_from_u64(0);
+    let setup = SetupSimple::new(NUM_SHARES_CASES[0], MSG_SIZE_CASES[0], rng);
+    let aad = setup.shared.aad.as_slice();
+
+    for ciphertexts_num in NUM_CIPHERTEXTS_CASES {
+        let decryption_shares = (0..ciphertexts_num)
+            .map(|_| {
+                let ciphertext = encrypt::<_, E>(
+                    &setup.shared.msg,
+                    aad,
+                    &setup.shared.pubkey,
+                    rng,
+                );
+                setup
+                    .contexts
+                    .iter()
+                    .map(|context| {
+                        context.create_share(&ciphertext, aad).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        group.bench_function(
+            BenchmarkId::new("share_combine_simple", ciphertexts_num),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        decryption_shares
+                            .iter()
+                            .map(|shares| {
+                                share_combine_simple::<E>(
+                                    shares,
+                                    &setup.lagrange_coeffs,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("share_combine_simple_batch", ciphertexts_num),
+            |b| {
+                b.iter(|| {
+                    black_box(share_combine_simple_batch::<E>(
+                        &decryption_shares,
+                        &setup.lagrange_coeffs,
+                    ))
+                })
+            },
+        );
+    }
+}
+
 pub fn bench_share_encrypt_decrypt(c: &mut Criterion) {
     let mut group = c.benchmark_group("ENCRYPT DECRYPT");
     group.sample_size(10);
@@ -397,6 +556,57 @@ pub fn bench_ciphertext_validity_checks(c: &mut Criterion) {
     }
 }
 
+pub fn bench_ciphertext_validity_checks_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CIPHERTEXT BATCH VERIFICATION");
+    group.sample_size(10);
+
+    let rng = &mut StdRng::seed_from_u64(0);
+    let setup = SetupFast::new(NUM_SHARES_CASES[0], MSG_SIZE_CASES[0], rng);
+    let aad = setup.shared.aad.as_slice();
+    let g_inv = &setup.contexts[0].setup_params.g_inv;
+
+    for ciphertexts_num in NUM_CIPHERTEXTS_CASES {
+        let ciphertexts = (0..ciphertexts_num)
+            .map(|_| {
+                encrypt::<_, E>(
+                    &setup.shared.msg,
+                    aad,
+                    &setup.shared.pubkey,
+                    rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        let batch = ciphertexts.iter().map(|c| (c, aad)).collect::<Vec<_>>();
+
+        group.bench_function(
+            BenchmarkId::new("ciphertext_verification", ciphertexts_num),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        batch
+                            .iter()
+                            .map(|(c, aad)| {
+                                check_ciphertext_validity(c, aad, g_inv)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("ciphertext_verification_batch", ciphertexts_num),
+            |b| {
+                let mut rng = rng.clone();
+                b.iter(|| {
+                    black_box(check_ciphertext_validity_batch(
+                        &batch, g_inv, &mut rng,
+                    ))
+                })
+            },
+        );
+    }
+}
+
 pub fn bench_decryption_share_validity_checks(c: &mut Criterion) {
     let mut group = c.benchmark_group("DECRYPTION SHARE VERIFICATION");
     group.sample_size(10);
@@ -564,10 +774,14 @@ pub fn bench_refresh_shares(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_create_decryption_share,
+    bench_create_share_bundle,
     bench_share_prepare,
+    bench_lagrange_coeffs,
     bench_share_combine,
+    bench_share_combine_batch,
     bench_share_encrypt_decrypt,
     bench_ciphertext_validity_checks,
+    bench_ciphertext_validity_checks_batch,
     bench_decryption_share_validity_checks,
     bench_recover_share_at_point,
     bench_refresh_shares,
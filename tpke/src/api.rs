@@ -5,22 +5,27 @@
 // TODO: Refactor this module to deduplicate shared code from tpke-wasm and tpke-wasm.
 
 use std::convert::TryInto;
+use std::fmt;
 
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{BigInteger256, ToBytes};
+use rand_core::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 // Fixing some of the types here on our target engine
 // TODO: Consider fixing on crate::api level instead of bindings level
 type E = ark_bls12_381::Bls12_381;
 type TpkePublicKey = ark_bls12_381::G1Affine;
 type TpkePrivateKey = ark_bls12_381::G2Affine;
+type TpkePreparedPublicKey = crate::PreparedPublicKey<E>;
 type TpkeCiphertext = crate::Ciphertext<E>;
 type TpkeDecryptionShare = crate::DecryptionShareFast<E>;
 type TpkePublicDecryptionContext = crate::PublicDecryptionContextFast<E>;
 type TpkeSharedSecret =
     <ark_bls12_381::Bls12_381 as ark_ec::PairingEngine>::Fqk;
 
-#[derive(Clone, Debug)]
+/// Zeroizes b^-1 on drop and redacts it from `Debug` output
+#[derive(Clone)]
 pub struct PrivateDecryptionContext {
     pub b_inv: ark_bls12_381::Fr,
     pub decrypter_index: usize,
@@ -41,6 +46,7 @@ impl PrivateDecryptionContext {
         Self::B_INV_LEN + Self::DECRYPTER_INDEX_LEN
     }
 
+    /// Exports the context, including the secret b^-1
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         self.b_inv.0.write(&mut bytes).unwrap();
@@ -78,6 +84,29 @@ impl PrivateDecryptionContext {
     }
 }
 
+impl fmt::Debug for PrivateDecryptionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateDecryptionContext")
+            .field("b_inv", &"<redacted>")
+            .field("decrypter_index", &self.decrypter_index)
+            .finish()
+    }
+}
+
+impl Zeroize for PrivateDecryptionContext {
+    fn zeroize(&mut self) {
+        self.b_inv.zeroize();
+    }
+}
+
+impl Drop for PrivateDecryptionContext {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateDecryptionContext {}
+
 #[derive(Clone, Debug)]
 pub struct DecryptionShare(pub TpkeDecryptionShare);
 
@@ -145,3 +174,26 @@ impl ParticipantPayload {
         })
     }
 }
+
+/// A public key prepared once for encrypting many messages
+#[derive(Clone, Debug)]
+pub struct PreparedPublicKey(pub TpkePreparedPublicKey);
+
+impl PreparedPublicKey {
+    pub fn new(public_key: &TpkePublicKey) -> Self {
+        Self(TpkePreparedPublicKey::new(public_key))
+    }
+
+    pub fn public_key(&self) -> TpkePublicKey {
+        self.0.public_key
+    }
+
+    pub fn encrypt<R: RngCore>(
+        &self,
+        message: &[u8],
+        aad: &[u8],
+        rng: &mut R,
+    ) -> TpkeCiphertext {
+        self.0.encrypt(message, aad, rng)
+    }
+}
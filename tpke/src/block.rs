@@ -0,0 +1,690 @@
+use crate::*;
+
+/// Decryption shares of a single validator for every valid ciphertext of a block
+#[derive(Debug, Clone)]
+pub struct DecryptionShareBundle<E: PairingEngine> {
+    pub decrypter_index: usize,
+    /// One share per valid ciphertext, in block order. Invalid ciphertexts are skipped.
+    pub decryption_shares: Vec<DecryptionShareFast<E>>,
+}
+
+/// A transaction of a block that could not be decrypted
+#[derive(Debug, Clone)]
+pub enum InvalidTransaction<E: PairingEngine> {
+    /// The ciphertext fails `TPKE.CiphertextValidity`, which anyone can check
+    InvalidCiphertext { index: usize },
+    /// The ciphertext is valid, but its payload does not decrypt under the combined shared secret
+    InvalidPayload { index: usize, shared_secret: E::Fqk },
+}
+
+impl<E: PairingEngine> InvalidTransaction<E> {
+    pub fn index(&self) -> usize {
+        match self {
+            Self::InvalidCiphertext { index } => *index,
+            Self::InvalidPayload { index, .. } => *index,
+        }
+    }
+}
+
+/// The decryption data a block proposer attaches to a block, so that full nodes can check it
+#[derive(Debug, Clone)]
+pub struct BlockDecryptionProof<E: PairingEngine> {
+    /// Index and 32-byte symmetric key of every valid transaction
+    pub symmetric_keys: Vec<(usize, [u8; 32])>,
+    /// Every invalid transaction, in block order
+    pub invalid_transactions: Vec<InvalidTransaction<E>>,
+    /// Decryption shares of the invalid payloads, aggregated across their ciphertexts
+    pub aggregated_shares: Option<AggregatedDecryptionShares<E>>,
+}
+
+/// The output of processing a block of encrypted transactions
+#[derive(Debug, Clone)]
+pub struct DecryptedBlock<E: HashToCurve> {
+    /// The plaintext of every transaction, or `None` for invalid transactions
+    pub plaintexts: Vec<Option<Vec<u8>>>,
+    /// Decrypters whose share bundles failed verification and were excluded
+    pub faulty_decrypters: Vec<usize>,
+    pub proof: BlockDecryptionProof<E>,
+}
+
+/// Decrypts a block of encrypted transactions, as done by the block proposer
+///
+/// See the "Encrypted Transactions" chapter of the Ferveo book. `aads[j]` is the additional
+/// authenticated data of `ciphertexts[j]`. Every bundle must come from a distinct member of
+/// the committee. Share bundles that fail verification are excluded, and at least `threshold`
+/// valid bundles are required.
+pub fn decrypt_block<R: RngCore, E: HashToCurve>(
+    pub_contexts: &[PublicDecryptionContextFast<E>],
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    share_bundles: &[DecryptionShareBundle<E>],
+    threshold: usize,
+    g_inv: &E::G1Prepared,
+    rng: &mut R,
+) -> Result<DecryptedBlock<E>> {
+    if aads.len() != ciphertexts.len() {
+        return Err(ThresholdEncryptionError::AadCountMismatch);
+    }
+    check_decrypters(
+        share_bundles.iter().map(|bundle| bundle.decrypter_index),
+        pub_contexts.len(),
+    )?;
+
+    let mut plaintexts = vec![None; ciphertexts.len()];
+    let mut symmetric_keys = vec![];
+    let mut invalid_transactions = vec![];
+
+    // TPKE.CiphertextValidity for every ciphertext of the block
+    let validity = check_ciphertext_validity_batch(
+        &zip_aads(ciphertexts, aads),
+        g_inv,
+        rng,
+    );
+    let mut valid_indices = vec![];
+    for (index, validity) in validity.into_iter().enumerate() {
+        if validity.is_ok() {
+            valid_indices.push(index);
+        } else {
+            invalid_transactions
+                .push(InvalidTransaction::InvalidCiphertext { index });
+        }
+    }
+    let valid_ciphertexts = valid_indices
+        .iter()
+        .map(|j| ciphertexts[*j].clone())
+        .collect::<Vec<_>>();
+
+    // Bundles must hold one share per valid ciphertext, created by their decrypter
+    let (mut bundles, mut faulty_decrypters): (Vec<_>, Vec<_>) =
+        share_bundles.iter().partition(|bundle| {
+            bundle.decryption_shares.len() == valid_ciphertexts.len()
+                && bundle
+                    .decryption_shares
+                    .iter()
+                    .all(|d| d.decrypter_index == bundle.decrypter_index)
+        });
+
+    if !valid_ciphertexts.is_empty() {
+        // TPKE.BatchVerifyDecryptionShares, with a fallback to TPKE.VerifyDecryptionShares
+        // for each decrypter in order to find the faulty ones
+        if !batch_verify_decryption_shares(
+            pub_contexts,
+            &valid_ciphertexts,
+            &shares_by_ciphertext(&bundles, valid_ciphertexts.len()),
+            rng,
+        ) {
+            let (valid, faulty): (Vec<_>, Vec<_>) =
+                bundles.into_iter().partition(|bundle| {
+                    let shares = bundle
+                        .decryption_shares
+                        .iter()
+                        .map(|d| vec![d.clone()])
+                        .collect::<Vec<_>>();
+                    batch_verify_decryption_shares(
+                        pub_contexts,
+                        &valid_ciphertexts,
+                        &shares,
+                        rng,
+                    )
+                });
+            bundles = valid;
+            faulty_decrypters.extend(faulty);
+        }
+        if bundles.len() < threshold {
+            return Err(ThresholdEncryptionError::InsufficientDecryptionShares);
+        }
+    }
+
+    // TPKE.CombineDecryptionShares, all ciphertexts share the same decrypters
+    let decryption_shares =
+        shares_by_ciphertext(&bundles, valid_ciphertexts.len());
+    let mut invalid_payloads = vec![];
+    if let Some(shares) = decryption_shares.first() {
+        let prepared_key_shares = prepare_combine_fast(pub_contexts, shares);
+        for (index, ciphertext, shares) in
+            izip!(valid_indices, valid_ciphertexts, decryption_shares)
+        {
+            let shared_secret =
+                share_combine_fast(&shares, &prepared_key_shares);
+            let symmetric_key = shared_secret_to_symmetric_key::<E>(
+                &shared_secret,
+                ciphertext.suite,
+            );
+            match decrypt_with_symmetric_key(&ciphertext, &symmetric_key) {
+                Ok(plaintext) => {
+                    plaintexts[index] = Some(plaintext);
+                    symmetric_keys.push((index, symmetric_key));
+                }
+                Err(_) => {
+                    invalid_transactions.push(
+                        InvalidTransaction::InvalidPayload {
+                            index,
+                            shared_secret,
+                        },
+                    );
+                    invalid_payloads.push((
+                        ciphertext,
+                        aads[index],
+                        shares,
+                        shared_secret,
+                    ));
+                }
+            }
+        }
+    }
+    invalid_transactions.sort_by_key(|tx| tx.index());
+
+    // TPKE.AggregateDecryptionShares across the invalid payloads
+    let aggregated_shares = if invalid_payloads.is_empty() {
+        None
+    } else {
+        let (mut ciphertexts, mut aads, mut shares, mut shared_secrets) =
+            (vec![], vec![], vec![], vec![]);
+        for (ciphertext, aad, shares_j, shared_secret) in invalid_payloads {
+            ciphertexts.push(ciphertext);
+            aads.push(aad);
+            shares.push(shares_j);
+            shared_secrets.push(shared_secret);
+        }
+        Some(aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &shares,
+            &shared_secrets,
+        )?)
+    };
+
+    Ok(DecryptedBlock {
+        plaintexts,
+        faulty_decrypters: faulty_decrypters
+            .iter()
+            .map(|bundle| bundle.decrypter_index)
+            .collect(),
+        proof: BlockDecryptionProof {
+            symmetric_keys,
+            invalid_transactions,
+            aggregated_shares,
+        },
+    })
+}
+
+/// Checks the decryption data of a block, as done by full nodes
+///
+/// Invalid payloads must be backed by the aggregated shares of at least `threshold`
+/// decrypters, as in [`decrypt_block`]. Returns the plaintext of every transaction, or
+/// `None` for invalid transactions
+pub fn verify_block_decryption<R: RngCore, E: HashToCurve>(
+    pub_contexts: &[PublicDecryptionContextFast<E>],
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    proof: &BlockDecryptionProof<E>,
+    threshold: usize,
+    g_inv: &E::G1Prepared,
+    rng: &mut R,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let fail = || ThresholdEncryptionError::BlockDecryptionVerificationFailed;
+    if aads.len() != ciphertexts.len() {
+        return Err(ThresholdEncryptionError::AadCountMismatch);
+    }
+
+    // Every transaction must be accounted for exactly once
+    let mut indices = proof
+        .symmetric_keys
+        .iter()
+        .map(|(index, _)| *index)
+        .chain(proof.invalid_transactions.iter().map(|tx| tx.index()))
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+    if indices != (0..ciphertexts.len()).collect::<Vec<_>>() {
+        return Err(fail());
+    }
+
+    let validity = check_ciphertext_validity_batch(
+        &zip_aads(ciphertexts, aads),
+        g_inv,
+        rng,
+    );
+
+    let mut plaintexts = vec![None; ciphertexts.len()];
+    for (index, symmetric_key) in proof.symmetric_keys.iter() {
+        if validity[*index].is_err() {
+            return Err(ThresholdEncryptionError::CiphertextVerificationFailed);
+        }
+        plaintexts[*index] = Some(decrypt_with_symmetric_key(
+            &ciphertexts[*index],
+            symmetric_key,
+        )?);
+    }
+
+    let mut invalid_payloads = vec![];
+    let mut invalid_aads = vec![];
+    let mut shared_secrets = vec![];
+    for tx in proof.invalid_transactions.iter() {
+        let index = tx.index();
+        let is_valid = validity[index].is_ok();
+        match tx {
+            InvalidTransaction::InvalidCiphertext { .. } if !is_valid => {}
+            InvalidTransaction::InvalidPayload { shared_secret, .. }
+                if is_valid =>
+            {
+                invalid_payloads.push(ciphertexts[index].clone());
+                invalid_aads.push(aads[index]);
+                shared_secrets.push(*shared_secret);
+            }
+            _ => return Err(fail()),
+        }
+    }
+
+    if !invalid_payloads.is_empty() {
+        // TPKE.VerifyAggregatedDecryptionShares and TPKE.VerifyAggregatedCombination
+        let aggregated = proof.aggregated_shares.as_ref().ok_or_else(fail)?;
+        check_decrypters(
+            aggregated.shares.iter().map(|d| d.decrypter_index),
+            pub_contexts.len(),
+        )?;
+        if aggregated.shares.len() < threshold {
+            return Err(ThresholdEncryptionError::InsufficientDecryptionShares);
+        }
+        if !verify_aggregated_decryption_shares(
+            pub_contexts,
+            &invalid_payloads,
+            &invalid_aads,
+            &shared_secrets,
+            aggregated,
+        ) || !verify_aggregated_combination(
+            pub_contexts,
+            &invalid_payloads,
+            &invalid_aads,
+            &shared_secrets,
+            aggregated,
+        ) {
+            return Err(fail());
+        }
+        // The payloads must actually fail to decrypt
+        let all_undecryptable = izip!(&invalid_payloads, &shared_secrets).all(
+            |(ciphertext, shared_secret)| {
+                decrypt_with_shared_secret(ciphertext, shared_secret).is_err()
+            },
+        );
+        if !all_undecryptable {
+            return Err(fail());
+        }
+    }
+
+    Ok(plaintexts)
+}
+
+/// Checks that every decrypter is a distinct member of a committee of `committee_size`
+fn check_decrypters(
+    decrypter_indices: impl Iterator<Item = usize>,
+    committee_size: usize,
+) -> Result<()> {
+    let mut seen = vec![false; committee_size];
+    for index in decrypter_indices {
+        match seen.get_mut(index) {
+            Some(seen) if !*seen => *seen = true,
+            _ => return Err(ThresholdEncryptionError::InvalidDecrypter),
+        }
+    }
+    Ok(())
+}
+
+/// Pairs every ciphertext with its additional authenticated data
+fn zip_aads<'a, E: PairingEngine>(
+    ciphertexts: &'a [Ciphertext<E>],
+    aads: &[&'a [u8]],
+) -> Vec<(&'a Ciphertext<E>, &'a [u8])> {
+    izip!(ciphertexts, aads.iter().copied()).collect()
+}
+
+/// Transposes per-decrypter bundles into per-ciphertext decryption shares
+fn shares_by_ciphertext<E: PairingEngine>(
+    bundles: &[&DecryptionShareBundle<E>],
+    num_ciphertexts: usize,
+) -> Vec<Vec<DecryptionShareFast<E>>> {
+    (0..num_ciphertexts)
+        .map(|j| {
+            bundles
+                .iter()
+                .map(|bundle| bundle.decryption_shares[j].clone())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn block_decryption_with_invalid_transactions() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+
+        let mut ciphertexts = (0..4)
+            .map(|j| {
+                let msg = format!("tx-{}", j);
+                encrypt::<_, E>(msg.as_bytes(), aad, &pubkey, rng)
+            })
+            .collect::<Vec<_>>();
+        // A transaction with a payload encrypted to the wrong key, but a valid ciphertext
+        let (other_pubkey, _, _) = setup_fast::<E>(threshold, shares_num, rng);
+        ciphertexts.push(encrypt::<_, E>(b"bad", aad, &other_pubkey, rng));
+        // A transaction with an invalid ciphertext
+        let mut malformed = ciphertexts[0].clone();
+        malformed.ciphertext[0] ^= 1;
+        ciphertexts.push(malformed);
+        let aads = vec![aad; ciphertexts.len()];
+
+        let mut share_bundles = contexts
+            .iter()
+            .map(|ctx| ctx.create_share_bundle(&ciphertexts, &aads))
+            .collect::<Vec<_>>();
+        // One validator sends a bad share
+        share_bundles[1].decryption_shares[0].decryption_share =
+            ciphertexts[0].commitment;
+
+        let block = decrypt_block(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &share_bundles,
+            threshold,
+            g_inv,
+            rng,
+        )
+        .unwrap();
+
+        assert_eq!(block.faulty_decrypters, vec![1]);
+        for j in 0..4 {
+            assert_eq!(
+                block.plaintexts[j].as_ref().unwrap(),
+                format!("tx-{}", j).as_bytes()
+            );
+        }
+        assert!(block.plaintexts[4].is_none());
+        assert!(block.plaintexts[5].is_none());
+        assert!(matches!(
+            block.proof.invalid_transactions[..],
+            [
+                InvalidTransaction::InvalidPayload { index: 4, .. },
+                InvalidTransaction::InvalidCiphertext { index: 5 }
+            ]
+        ));
+
+        // A full node checks the work of the block proposer
+        let plaintexts = verify_block_decryption(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &block.proof,
+            threshold,
+            g_inv,
+            rng,
+        )
+        .unwrap();
+        assert_eq!(plaintexts, block.plaintexts);
+
+        // A proposer can not censor a valid transaction
+        let mut censored = block.proof.clone();
+        censored.symmetric_keys.remove(0);
+        censored
+            .invalid_transactions
+            .push(InvalidTransaction::InvalidCiphertext { index: 0 });
+        assert!(verify_block_decryption(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &censored,
+            threshold,
+            g_inv,
+            rng,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn block_decryption_requires_threshold_shares() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let ciphertexts = vec![encrypt::<_, E>(b"tx", aad, &pubkey, rng)];
+        let aads = vec![aad];
+
+        let share_bundles = contexts
+            .iter()
+            .take(threshold - 1)
+            .map(|ctx| ctx.create_share_bundle(&ciphertexts, &aads))
+            .collect::<Vec<_>>();
+
+        assert!(decrypt_block(
+            &contexts[0].public_decryption_contexts,
+            &ciphertexts,
+            &aads,
+            &share_bundles,
+            threshold,
+            g_inv,
+            rng,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn block_decryption_rejects_invalid_inputs() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertexts = vec![encrypt::<_, E>(b"tx", aad, &pubkey, rng)];
+        let aads = vec![aad];
+        let share_bundles = contexts
+            .iter()
+            .map(|ctx| ctx.create_share_bundle(&ciphertexts, &aads))
+            .collect::<Vec<_>>();
+        let decrypt = |aads: &[&[u8]],
+                       share_bundles: &[DecryptionShareBundle<E>],
+                       rng: &mut _| {
+            decrypt_block(
+                pub_contexts,
+                &ciphertexts,
+                aads,
+                share_bundles,
+                threshold,
+                g_inv,
+                rng,
+            )
+        };
+
+        // Duplicate bundles do not count towards the threshold
+        let mut duplicated = share_bundles[..threshold - 1].to_vec();
+        duplicated.push(share_bundles[0].clone());
+        assert!(matches!(
+            decrypt(&aads, &duplicated, rng),
+            Err(ThresholdEncryptionError::InvalidDecrypter)
+        ));
+
+        // Bundles from outside the committee are rejected
+        let mut unknown = share_bundles.clone();
+        unknown[0].decrypter_index = shares_num;
+        for d in unknown[0].decryption_shares.iter_mut() {
+            d.decrypter_index = shares_num;
+        }
+        assert!(matches!(
+            decrypt(&aads, &unknown, rng),
+            Err(ThresholdEncryptionError::InvalidDecrypter)
+        ));
+
+        // Every ciphertext needs its aad
+        assert!(matches!(
+            decrypt(&[], &share_bundles, rng),
+            Err(ThresholdEncryptionError::AadCountMismatch)
+        ));
+        let block = decrypt(&aads, &share_bundles, rng).unwrap();
+        assert!(matches!(
+            verify_block_decryption(
+                pub_contexts,
+                &ciphertexts,
+                &[],
+                &block.proof,
+                threshold,
+                g_inv,
+                rng,
+            ),
+            Err(ThresholdEncryptionError::AadCountMismatch)
+        ));
+    }
+
+    #[test]
+    fn block_decryption_rejects_forged_invalid_payloads() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertexts = (0..2)
+            .map(|j| {
+                let msg = format!("tx-{}", j);
+                encrypt::<_, E>(msg.as_bytes(), aad, &pubkey, rng)
+            })
+            .collect::<Vec<_>>();
+        let aads = vec![aad; ciphertexts.len()];
+
+        // The proposer knows the honest shares and shared secrets of both transactions
+        let decryption_shares = ciphertexts
+            .iter()
+            .map(|c| {
+                contexts
+                    .iter()
+                    .map(|ctx| ctx.create_share(c, aad, g_inv).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let shared_secrets = decryption_shares
+            .iter()
+            .map(|shares| {
+                let prepared = prepare_combine_fast(pub_contexts, shares);
+                share_combine_fast(shares, &prepared)
+            })
+            .collect::<Vec<_>>();
+
+        // and tries to censor both by claiming compensating shared secrets
+        let rho =
+            aggregation_coefficients(&ciphertexts, &aads, &shared_secrets);
+        let x = <E as PairingEngine>::Fqk::rand(rng);
+        let forged_shared_secrets = vec![
+            shared_secrets[0] * x.pow(rho[1].into_repr()),
+            shared_secrets[1] * x.pow(rho[0].into_repr()).inverse().unwrap(),
+        ];
+        let aggregated_shares = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &forged_shared_secrets,
+        )
+        .unwrap();
+        let proof = BlockDecryptionProof {
+            symmetric_keys: vec![],
+            invalid_transactions: forged_shared_secrets
+                .into_iter()
+                .enumerate()
+                .map(|(index, shared_secret)| {
+                    InvalidTransaction::InvalidPayload {
+                        index,
+                        shared_secret,
+                    }
+                })
+                .collect(),
+            aggregated_shares: Some(aggregated_shares),
+        };
+
+        assert!(matches!(
+            verify_block_decryption(
+                pub_contexts,
+                &ciphertexts,
+                &aads,
+                &proof,
+                threshold,
+                g_inv,
+                rng,
+            ),
+            Err(ThresholdEncryptionError::BlockDecryptionVerificationFailed)
+        ));
+    }
+
+    #[test]
+    fn block_decryption_proof_requires_threshold_shares() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertexts = vec![encrypt::<_, E>(b"tx", aad, &pubkey, rng)];
+        let aads = vec![aad];
+
+        // The proposer combines the share of a single honest decrypter, whose
+        // Lagrange coefficient is 1, and claims the wrong shared secret is invalid
+        let decryption_shares = vec![vec![contexts[0]
+            .create_share(&ciphertexts[0], aad, g_inv)
+            .unwrap()]];
+        let prepared =
+            prepare_combine_fast(pub_contexts, &decryption_shares[0]);
+        let shared_secrets =
+            vec![share_combine_fast(&decryption_shares[0], &prepared)];
+        let aggregated_shares = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &shared_secrets,
+        )
+        .unwrap();
+        let proof = BlockDecryptionProof {
+            symmetric_keys: vec![],
+            invalid_transactions: vec![InvalidTransaction::InvalidPayload {
+                index: 0,
+                shared_secret: shared_secrets[0],
+            }],
+            aggregated_shares: Some(aggregated_shares),
+        };
+
+        let verify = |threshold: usize, rng: &mut _| {
+            verify_block_decryption(
+                pub_contexts,
+                &ciphertexts,
+                &aads,
+                &proof,
+                threshold,
+                g_inv,
+                rng,
+            )
+        };
+        // Both aggregation checks pass for a single decrypter
+        assert!(verify(1, rng).is_ok());
+        assert!(matches!(
+            verify(threshold, rng),
+            Err(ThresholdEncryptionError::InsufficientDecryptionShares)
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+use crate::*;
+
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::{
+    aead::{
+        generic_array::{typenum::Unsigned, GenericArray},
+        Aead, AeadCore, KeyInit,
+    },
+    ChaCha20Poly1305, XChaCha20Poly1305,
+};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+
+/// Derives the 32-byte symmetric key of a ciphertext from its serialized shared secret
+pub trait Kdf {
+    fn derive_key(shared_secret: &[u8]) -> [u8; 32];
+}
+
+/// Authenticated encryption of the payload of a ciphertext
+///
+/// The nonce is derived from the commitment U, which is fresh for every ciphertext
+pub trait SymmetricAead {
+    const NONCE_LEN: usize;
+    const TAG_LEN: usize;
+
+    fn encrypt(key: &[u8; 32], nonce: &[u8], message: &[u8]) -> Vec<u8>;
+
+    fn decrypt(
+        key: &[u8; 32],
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>>;
+}
+
+/// A KDF and AEAD pair, recorded in the ciphertext header by its `ID`
+pub trait CipherSuite {
+    const ID: CipherSuiteId;
+    type Kdf: Kdf;
+    type Aead: SymmetricAead;
+}
+
+/// Identifies the cipher suite of a ciphertext
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CipherSuiteId {
+    /// SHA-256 and ChaCha20-Poly1305
+    #[default]
+    ChaCha20Poly1305Sha256 = 0,
+    /// HKDF-SHA256 and AES-256-GCM
+    Aes256GcmHkdfSha256 = 1,
+    /// HKDF-SHA256 and XChaCha20-Poly1305
+    XChaCha20Poly1305HkdfSha256 = 2,
+}
+
+impl CipherSuiteId {
+    pub fn from_u8(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::ChaCha20Poly1305Sha256),
+            1 => Some(Self::Aes256GcmHkdfSha256),
+            2 => Some(Self::XChaCha20Poly1305HkdfSha256),
+            _ => None,
+        }
+    }
+
+    pub fn nonce_len(self) -> usize {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => {
+                <ChaCha20Poly1305Sha256 as CipherSuite>::Aead::NONCE_LEN
+            }
+            Self::Aes256GcmHkdfSha256 => {
+                <Aes256GcmHkdfSha256 as CipherSuite>::Aead::NONCE_LEN
+            }
+            Self::XChaCha20Poly1305HkdfSha256 => {
+                <XChaCha20Poly1305HkdfSha256 as CipherSuite>::Aead::NONCE_LEN
+            }
+        }
+    }
+
+    pub fn tag_len(self) -> usize {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => {
+                <ChaCha20Poly1305Sha256 as CipherSuite>::Aead::TAG_LEN
+            }
+            Self::Aes256GcmHkdfSha256 => {
+                <Aes256GcmHkdfSha256 as CipherSuite>::Aead::TAG_LEN
+            }
+            Self::XChaCha20Poly1305HkdfSha256 => {
+                <XChaCha20Poly1305HkdfSha256 as CipherSuite>::Aead::TAG_LEN
+            }
+        }
+    }
+
+    pub fn derive_key(self, shared_secret: &[u8]) -> [u8; 32] {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => {
+                <ChaCha20Poly1305Sha256 as CipherSuite>::Kdf::derive_key(
+                    shared_secret,
+                )
+            }
+            Self::Aes256GcmHkdfSha256 => {
+                <Aes256GcmHkdfSha256 as CipherSuite>::Kdf::derive_key(
+                    shared_secret,
+                )
+            }
+            Self::XChaCha20Poly1305HkdfSha256 => {
+                <XChaCha20Poly1305HkdfSha256 as CipherSuite>::Kdf::derive_key(
+                    shared_secret,
+                )
+            }
+        }
+    }
+
+    pub(crate) fn encrypt(
+        self,
+        key: &[u8; 32],
+        nonce_seed: &[u8],
+        message: &[u8],
+    ) -> Vec<u8> {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => {
+                suite_encrypt::<ChaCha20Poly1305Sha256>(
+                    key, nonce_seed, message,
+                )
+            }
+            Self::Aes256GcmHkdfSha256 => {
+                suite_encrypt::<Aes256GcmHkdfSha256>(key, nonce_seed, message)
+            }
+            Self::XChaCha20Poly1305HkdfSha256 => {
+                suite_encrypt::<XChaCha20Poly1305HkdfSha256>(
+                    key, nonce_seed, message,
+                )
+            }
+        }
+    }
+
+    pub(crate) fn decrypt(
+        self,
+        key: &[u8; 32],
+        nonce_seed: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        match self {
+            Self::ChaCha20Poly1305Sha256 => {
+                suite_decrypt::<ChaCha20Poly1305Sha256>(
+                    key, nonce_seed, ciphertext,
+                )
+            }
+            Self::Aes256GcmHkdfSha256 => suite_decrypt::<Aes256GcmHkdfSha256>(
+                key, nonce_seed, ciphertext,
+            ),
+            Self::XChaCha20Poly1305HkdfSha256 => {
+                suite_decrypt::<XChaCha20Poly1305HkdfSha256>(
+                    key, nonce_seed, ciphertext,
+                )
+            }
+        }
+    }
+}
+
+fn suite_encrypt<S: CipherSuite>(
+    key: &[u8; 32],
+    nonce_seed: &[u8],
+    message: &[u8],
+) -> Vec<u8> {
+    S::Aead::encrypt(key, &nonce_seed[..S::Aead::NONCE_LEN], message)
+}
+
+fn suite_decrypt<S: CipherSuite>(
+    key: &[u8; 32],
+    nonce_seed: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    S::Aead::decrypt(key, &nonce_seed[..S::Aead::NONCE_LEN], ciphertext)
+}
+
+/// The original KDF of tpke: SHA-256 of the serialized shared secret
+pub struct Sha256Kdf;
+
+impl Kdf for Sha256Kdf {
+    fn derive_key(shared_secret: &[u8]) -> [u8; 32] {
+        Sha256::digest(shared_secret).into()
+    }
+}
+
+/// HKDF-SHA256 of the serialized shared secret
+pub struct HkdfSha256;
+
+impl HkdfSha256 {
+    const INFO: &'static [u8] = b"FERVEO_TPKE_SYMMETRIC_KEY";
+}
+
+impl Kdf for HkdfSha256 {
+    fn derive_key(shared_secret: &[u8]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, shared_secret)
+            .expand(Self::INFO, &mut key)
+            .unwrap();
+        key
+    }
+}
+
+fn aead_encrypt<A: Aead + KeyInit>(
+    key: &[u8; 32],
+    nonce: &[u8],
+    message: &[u8],
+) -> Vec<u8> {
+    A::new(GenericArray::from_slice(key))
+        .encrypt(GenericArray::from_slice(nonce), message)
+        .unwrap()
+}
+
+fn aead_decrypt<A: Aead + KeyInit>(
+    key: &[u8; 32],
+    nonce: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    A::new(GenericArray::from_slice(key))
+        .decrypt(GenericArray::from_slice(nonce), ciphertext)
+        .map_err(|_| ThresholdEncryptionError::PlaintextVerificationFailed)
+}
+
+macro_rules! impl_symmetric_aead {
+    ($aead:ty) => {
+        impl SymmetricAead for $aead {
+            const NONCE_LEN: usize =
+                <<$aead as AeadCore>::NonceSize as Unsigned>::USIZE;
+            const TAG_LEN: usize =
+                <<$aead as AeadCore>::TagSize as Unsigned>::USIZE;
+
+            fn encrypt(
+                key: &[u8; 32],
+                nonce: &[u8],
+                message: &[u8],
+            ) -> Vec<u8> {
+                aead_encrypt::<Self>(key, nonce, message)
+            }
+
+            fn decrypt(
+                key: &[u8; 32],
+                nonce: &[u8],
+                ciphertext: &[u8],
+            ) -> Result<Vec<u8>> {
+                aead_decrypt::<Self>(key, nonce, ciphertext)
+            }
+        }
+    };
+}
+
+impl_symmetric_aead!(ChaCha20Poly1305);
+impl_symmetric_aead!(XChaCha20Poly1305);
+impl_symmetric_aead!(Aes256Gcm);
+
+/// SHA-256 and ChaCha20-Poly1305, the default suite
+pub struct ChaCha20Poly1305Sha256;
+
+impl CipherSuite for ChaCha20Poly1305Sha256 {
+    const ID: CipherSuiteId = CipherSuiteId::ChaCha20Poly1305Sha256;
+    type Kdf = Sha256Kdf;
+    type Aead = ChaCha20Poly1305;
+}
+
+/// HKDF-SHA256 and AES-256-GCM, for deployments restricted to FIPS algorithms
+pub struct Aes256GcmHkdfSha256;
+
+impl CipherSuite for Aes256GcmHkdfSha256 {
+    const ID: CipherSuiteId = CipherSuiteId::Aes256GcmHkdfSha256;
+    type Kdf = HkdfSha256;
+    type Aead = Aes256Gcm;
+}
+
+/// HKDF-SHA256 and XChaCha20-Poly1305
+pub struct XChaCha20Poly1305HkdfSha256;
+
+impl CipherSuite for XChaCha20Poly1305HkdfSha256 {
+    const ID: CipherSuiteId = CipherSuiteId::XChaCha20Poly1305HkdfSha256;
+    type Kdf = HkdfSha256;
+    type Aead = XChaCha20Poly1305;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn every_suite_roundtrips() {
+        let rng = &mut test_rng();
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, privkey, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        for suite in [
+            CipherSuiteId::ChaCha20Poly1305Sha256,
+            CipherSuiteId::Aes256GcmHkdfSha256,
+            CipherSuiteId::XChaCha20Poly1305HkdfSha256,
+        ] {
+            for mode in [AeadMode::Standard, AeadMode::KeyCommitting] {
+                let ciphertext = encrypt_with_suite::<_, E>(
+                    msg, aad, &pubkey, suite, mode, rng,
+                );
+                let ciphertext: Ciphertext<E> =
+                    Ciphertext::from_bytes(&ciphertext.to_bytes());
+                assert_eq!(ciphertext.suite, suite);
+
+                let plaintext =
+                    checked_decrypt(&ciphertext, aad, g_inv, &privkey).unwrap();
+                assert_eq!(plaintext, msg);
+            }
+        }
+    }
+
+    #[test]
+    fn suite_id_is_authenticated() {
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        let mut ciphertext = encrypt_with_suite::<_, E>(
+            "abc".as_bytes(),
+            aad,
+            &pubkey,
+            CipherSuiteId::Aes256GcmHkdfSha256,
+            AeadMode::Standard,
+            rng,
+        );
+        assert!(check_ciphertext_validity(&ciphertext, aad, g_inv).is_ok());
+
+        ciphertext.suite = CipherSuiteId::XChaCha20Poly1305HkdfSha256;
+        assert!(check_ciphertext_validity(&ciphertext, aad, g_inv).is_err());
+    }
+}
@@ -4,22 +4,36 @@ use ark_ec::{AffineCurve, PairingEngine};
 use ark_ff::{FromBytes, One, ToBytes, UniformRand};
 use ark_serialize::CanonicalSerialize;
 use chacha20poly1305::{
-    aead::{generic_array::GenericArray, Aead, KeyInit},
-    ChaCha20Poly1305, Nonce,
+    aead::{generic_array::GenericArray, KeyInit},
+    ChaCha20Poly1305,
 };
-use crypto::{digest::Digest, sha2::Sha256};
 use rand_core::RngCore;
+use sha2::{Digest, Sha256};
 
-use crate::{construct_tag_hash, hash_to_g2};
+use crate::construct_tag_hash;
+
+/// Selects how the payload of a ciphertext is encrypted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AeadMode {
+    /// Plain AEAD of the cipher suite
+    Standard,
+    /// AEAD of the cipher suite with a commitment to the symmetric key, so that the
+    /// payload can only be decrypted under a single key
+    KeyCommitting,
+}
 
 #[derive(Clone, Debug)]
 pub struct Ciphertext<E: PairingEngine> {
     pub commitment: E::G1Affine, // U
     pub auth_tag: E::G2Affine,   // W
     pub ciphertext: Vec<u8>,     // V
+    /// KDF and AEAD used for the payload
+    pub suite: CipherSuiteId,
+    /// Commitment to the symmetric key, set in [`AeadMode::KeyCommitting`]
+    pub key_commitment: Option<[u8; 32]>,
 }
 
-impl<E: PairingEngine> Ciphertext<E> {
+impl<E: HashToCurve> Ciphertext<E> {
     pub fn check(&self, g_inv: &E::G1Prepared) -> bool {
         let hash_g2 = E::G2Prepared::from(self.construct_tag_hash());
 
@@ -32,47 +46,159 @@ impl<E: PairingEngine> Ciphertext<E> {
     fn construct_tag_hash(&self) -> E::G2Affine {
         let mut hash_input = Vec::<u8>::new();
         self.commitment.write(&mut hash_input).unwrap();
-        hash_input.extend_from_slice(&self.ciphertext);
+        hash_input.extend(self.authenticated_payload());
+
+        E::hash_to_g2(&hash_input, CIPHERTEXT_TAG_DST)
+    }
+}
 
-        hash_to_g2(&hash_input)
+impl<E: PairingEngine> Ciphertext<E> {
+    pub fn mode(&self) -> AeadMode {
+        match self.key_commitment {
+            Some(_) => AeadMode::KeyCommitting,
+            None => AeadMode::Standard,
+        }
+    }
+
+    /// The payload covered by the tag W: the header followed by V
+    fn authenticated_payload(&self) -> Vec<u8> {
+        authenticated_payload(
+            self.suite,
+            self.key_commitment.as_ref(),
+            &self.ciphertext,
+        )
     }
 
+    const COMMITMENT_LEN: usize = 97;
+    const AUTH_TAG_LEN: usize = 193;
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         self.commitment.write(&mut bytes).unwrap();
         self.auth_tag.write(&mut bytes).unwrap();
+        bytes.extend(self.authenticated_payload());
         bytes
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Self {
-        const COMMITMENT_LEN: usize = 97;
-        let mut commitment_bytes = [0u8; COMMITMENT_LEN];
-        commitment_bytes.copy_from_slice(&bytes[..COMMITMENT_LEN]);
-        let commitment = E::G1Affine::read(&commitment_bytes[..]).unwrap();
+        Self::try_from_bytes(bytes).unwrap()
+    }
 
-        const AUTH_TAG_LEN: usize = 193;
-        let mut auth_tag_bytes = [0u8; AUTH_TAG_LEN];
-        auth_tag_bytes.copy_from_slice(
-            &bytes[COMMITMENT_LEN..COMMITMENT_LEN + AUTH_TAG_LEN],
-        );
-        let auth_tag = E::G2Affine::read(&auth_tag_bytes[..]).unwrap();
+    /// Like [`Ciphertext::from_bytes`], but fails on malformed input instead of panicking
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::COMMITMENT_LEN + Self::AUTH_TAG_LEN {
+            return None;
+        }
+        let (commitment_bytes, rest) = bytes.split_at(Self::COMMITMENT_LEN);
+        let (auth_tag_bytes, rest) = rest.split_at(Self::AUTH_TAG_LEN);
+
+        let commitment = E::G1Affine::read(commitment_bytes).ok()?;
+        let auth_tag = E::G2Affine::read(auth_tag_bytes).ok()?;
 
-        let ciphertext = bytes[COMMITMENT_LEN + AUTH_TAG_LEN..].to_vec();
+        let (suite, rest) = rest.split_first()?;
+        let suite = CipherSuiteId::from_u8(*suite)?;
+        let (key_commitment, ciphertext) = match rest.split_first()? {
+            (&STANDARD_MODE, ciphertext) => (None, ciphertext),
+            (&KEY_COMMITTING_MODE, rest) if rest.len() >= 32 => {
+                let (key_commitment_bytes, ciphertext) = rest.split_at(32);
+                let mut key_commitment = [0u8; 32];
+                key_commitment.copy_from_slice(key_commitment_bytes);
+                (Some(key_commitment), ciphertext)
+            }
+            _ => return None,
+        };
 
-        Self {
+        Some(Self {
             commitment,
-            ciphertext,
+            ciphertext: ciphertext.to_vec(),
             auth_tag,
-        }
+            suite,
+            key_commitment,
+        })
     }
 }
 
-pub fn encrypt<R: RngCore, E: PairingEngine>(
+const STANDARD_MODE: u8 = 0;
+const KEY_COMMITTING_MODE: u8 = 1;
+
+pub fn encrypt<R: RngCore, E: HashToCurve>(
+    message: &[u8],
+    aad: &[u8],
+    pubkey: &E::G1Affine,
+    rng: &mut R,
+) -> Ciphertext<E> {
+    encrypt_with_mode(message, aad, pubkey, AeadMode::Standard, rng)
+}
+
+/// Like [`encrypt`], with the AEAD mode of the payload selected by `mode`
+pub fn encrypt_with_mode<R: RngCore, E: HashToCurve>(
+    message: &[u8],
+    aad: &[u8],
+    pubkey: &E::G1Affine,
+    mode: AeadMode,
+    rng: &mut R,
+) -> Ciphertext<E> {
+    encrypt_with_suite(
+        message,
+        aad,
+        pubkey,
+        CipherSuiteId::default(),
+        mode,
+        rng,
+    )
+}
+
+/// Like [`encrypt_with_mode`], with the KDF and AEAD selected by `suite`
+pub fn encrypt_with_suite<R: RngCore, E: HashToCurve>(
     message: &[u8],
     aad: &[u8],
     pubkey: &E::G1Affine,
+    suite: CipherSuiteId,
+    mode: AeadMode,
     rng: &mut R,
 ) -> Ciphertext<E> {
+    let (rand_element, commitment, symmetric_key) =
+        encapsulate_symmetric_key::<R, E>(pubkey, suite, rng);
+    encrypt_payload(
+        rand_element,
+        commitment,
+        &symmetric_key,
+        message,
+        suite,
+        mode,
+        aad,
+    )
+}
+
+/// Encrypts `message` into the payload V and seals it, see [`seal`]
+pub(crate) fn encrypt_payload<E: HashToCurve>(
+    rand_element: E::Fr,
+    commitment: E::G1Affine,
+    symmetric_key: &[u8; 32],
+    message: &[u8],
+    suite: CipherSuiteId,
+    mode: AeadMode,
+    aad: &[u8],
+) -> Ciphertext<E> {
+    let nonce_seed = nonce_seed_from_commitment::<E>(commitment);
+    let ciphertext = suite.encrypt(symmetric_key, &nonce_seed, message);
+    seal(
+        rand_element,
+        commitment,
+        ciphertext,
+        symmetric_key,
+        suite,
+        mode,
+        aad,
+    )
+}
+
+/// Samples r and returns r, U = [r] G and the symmetric key derived from e([r] Y, H)
+pub(crate) fn encapsulate_symmetric_key<R: RngCore, E: PairingEngine>(
+    pubkey: &E::G1Affine,
+    suite: CipherSuiteId,
+    rng: &mut R,
+) -> (E::Fr, E::G1Affine, [u8; 32]) {
     // r
     let rand_element = E::Fr::rand(rng);
     // g
@@ -86,26 +212,47 @@ pub fn encrypt<R: RngCore, E: PairingEngine>(
     // u
     let commitment = g_gen.mul(rand_element).into();
 
-    let cipher = shared_secret_to_chacha::<E>(&product);
-    let nonce = nonce_from_commitment::<E>(commitment);
-    let ciphertext = cipher.encrypt(&nonce, message).unwrap();
+    let symmetric_key = shared_secret_to_symmetric_key::<E>(&product, suite);
+    (rand_element, commitment, symmetric_key)
+}
+
+/// Computes the tag W over the header, the payload V and `aad`
+pub(crate) fn seal<E: HashToCurve>(
+    rand_element: E::Fr,
+    commitment: E::G1Affine,
+    ciphertext: Vec<u8>,
+    symmetric_key: &[u8; 32],
+    suite: CipherSuiteId,
+    mode: AeadMode,
+    aad: &[u8],
+) -> Ciphertext<E> {
+    let key_commitment = match mode {
+        AeadMode::Standard => None,
+        AeadMode::KeyCommitting => Some(commit_to_key(symmetric_key)),
+    };
     // w
-    let auth_tag = construct_tag_hash::<E>(commitment, &ciphertext, aad)
-        .mul(rand_element)
-        .into();
+    let auth_tag = construct_tag_hash::<E>(
+        commitment,
+        &authenticated_payload(suite, key_commitment.as_ref(), &ciphertext),
+        aad,
+    )
+    .mul(rand_element)
+    .into();
 
     // TODO: Consider adding aad to the Ciphertext struct
     Ciphertext::<E> {
         commitment,
         ciphertext,
         auth_tag,
+        suite,
+        key_commitment,
     }
 }
 
 /// Implements the check section 4.4.2 of the Ferveo paper, 'TPKE.CheckCiphertextValidity(U,W,aad)'
 /// See: https://eprint.iacr.org/2022/898.pdf
 /// See: https://nikkolasg.github.io/ferveo/tpke.html#to-validate-ciphertext-for-ind-cca2-security
-pub fn check_ciphertext_validity<E: PairingEngine>(
+pub fn check_ciphertext_validity<E: HashToCurve>(
     c: &Ciphertext<E>,
     aad: &[u8],
     g_inv: &E::G1Prepared,
@@ -113,7 +260,7 @@ pub fn check_ciphertext_validity<E: PairingEngine>(
     // H_G2(U, aad)
     let hash_g2 = E::G2Prepared::from(construct_tag_hash::<E>(
         c.commitment,
-        &c.ciphertext[..],
+        &c.authenticated_payload(),
         aad,
     ));
 
@@ -130,7 +277,88 @@ pub fn check_ciphertext_validity<E: PairingEngine>(
     }
 }
 
-pub fn checked_decrypt<E: PairingEngine>(
+/// Checks the validity of many (ciphertext, aad) pairs, such as the transactions of a block,
+/// with one randomized multi-pairing
+///
+/// Combines the equations of [`check_ciphertext_validity`] with random scalars a_j into
+/// e(sum_j [a_j] U_j, H_G2(U_j, aad_j)) = e(G, sum_j [a_j] W_j). If the batch fails, it is
+/// bisected to find the invalid ciphertexts. Returns one result per ciphertext.
+pub fn check_ciphertext_validity_batch<R: RngCore, E: HashToCurve>(
+    ciphertexts: &[(&Ciphertext<E>, &[u8])],
+    g_inv: &E::G1Prepared,
+    rng: &mut R,
+) -> Vec<Result<()>> {
+    let alphas = (0..ciphertexts.len())
+        .map(|_| E::Fr::rand(rng))
+        .collect::<Vec<_>>();
+
+    let equations: Vec<ValidityEquation<E>> = cfg_iter!(ciphertexts)
+        .zip(cfg_iter!(alphas))
+        .map(|((c, aad), alpha)| {
+            let hash_g2 = construct_tag_hash::<E>(
+                c.commitment,
+                &c.authenticated_payload(),
+                aad,
+            );
+            (
+                (
+                    E::G1Prepared::from(c.commitment.mul(*alpha).into()),
+                    E::G2Prepared::from(hash_g2),
+                ),
+                c.auth_tag.mul(*alpha),
+            )
+        })
+        .collect();
+
+    let mut results = ciphertexts.iter().map(|_| Ok(())).collect::<Vec<_>>();
+    bisect_ciphertext_validity::<E>(&equations, g_inv, &mut results);
+    results
+}
+
+/// The randomized validity equation of a ciphertext, ([a] U, H_G2(U, aad)) and [a] W
+type ValidityEquation<E> = (
+    (
+        <E as PairingEngine>::G1Prepared,
+        <E as PairingEngine>::G2Prepared,
+    ),
+    <E as PairingEngine>::G2Projective,
+);
+
+/// Marks the results of the ciphertexts whose `equations` do not hold as invalid
+fn bisect_ciphertext_validity<E: PairingEngine>(
+    equations: &[ValidityEquation<E>],
+    g_inv: &E::G1Prepared,
+    results: &mut [Result<()>],
+) {
+    if equations.is_empty() {
+        return;
+    }
+    let mut pairs = equations
+        .iter()
+        .map(|(pair, _)| pair.clone())
+        .collect::<Vec<_>>();
+    let auth_tags = equations
+        .iter()
+        .map(|(_, auth_tag)| *auth_tag)
+        .sum::<E::G2Projective>();
+    pairs.push((g_inv.clone(), E::G2Prepared::from(auth_tags.into())));
+    if E::product_of_pairings(&pairs) == E::Fqk::one() {
+        return;
+    }
+
+    if equations.len() == 1 {
+        results[0] =
+            Err(ThresholdEncryptionError::CiphertextVerificationFailed);
+    } else {
+        let mid = equations.len() / 2;
+        let (left, right) = equations.split_at(mid);
+        let (left_results, right_results) = results.split_at_mut(mid);
+        bisect_ciphertext_validity::<E>(left, g_inv, left_results);
+        bisect_ciphertext_validity::<E>(right, g_inv, right_results);
+    }
+}
+
+pub fn checked_decrypt<E: HashToCurve>(
     ciphertext: &Ciphertext<E>,
     aad: &[u8],
     g_inv: &E::G1Prepared,
@@ -141,55 +369,125 @@ pub fn checked_decrypt<E: PairingEngine>(
         E::G1Prepared::from(ciphertext.commitment),
         E::G2Prepared::from(*privkey),
     )]);
-    Ok(decrypt_with_shared_secret(ciphertext, &s))
+    decrypt_with_shared_secret(ciphertext, &s)
 }
 
-fn decrypt_with_shared_secret<E: PairingEngine>(
+pub(crate) fn decrypt_with_shared_secret<E: PairingEngine>(
     ciphertext: &Ciphertext<E>,
     s: &E::Fqk,
-) -> Vec<u8> {
-    let nonce = nonce_from_commitment::<E>(ciphertext.commitment);
-    let ciphertext = ciphertext.ciphertext.to_vec();
-
-    let cipher = shared_secret_to_chacha::<E>(s);
-    let plaintext = cipher.decrypt(&nonce, ciphertext.as_ref()).unwrap();
+) -> Result<Vec<u8>> {
+    decrypt_with_symmetric_key(
+        ciphertext,
+        &shared_secret_to_symmetric_key::<E>(s, ciphertext.suite),
+    )
+}
 
-    plaintext
+/// Decrypts the payload of a ciphertext with the symmetric key derived from its shared secret,
+/// using the cipher suite recorded in the ciphertext
+///
+/// Does not check the ciphertext validity, see [`check_ciphertext_validity`].
+/// In [`AeadMode::KeyCommitting`], fails if `symmetric_key` does not match the key commitment.
+pub fn decrypt_with_symmetric_key<E: PairingEngine>(
+    ciphertext: &Ciphertext<E>,
+    symmetric_key: &[u8; 32],
+) -> Result<Vec<u8>> {
+    check_key_commitment(ciphertext, symmetric_key)?;
+    let nonce_seed = nonce_seed_from_commitment::<E>(ciphertext.commitment);
+    ciphertext
+        .suite
+        .decrypt(symmetric_key, &nonce_seed, &ciphertext.ciphertext)
 }
 
-pub fn checked_decrypt_with_shared_secret<E: PairingEngine>(
+pub fn checked_decrypt_with_shared_secret<E: HashToCurve>(
     ciphertext: &Ciphertext<E>,
     aad: &[u8],
     g_inv: &E::G1Prepared,
     shared_secret: &E::Fqk,
 ) -> Result<Vec<u8>> {
     check_ciphertext_validity(ciphertext, aad, g_inv)?;
-    Ok(decrypt_with_shared_secret(ciphertext, shared_secret))
+    decrypt_with_shared_secret(ciphertext, shared_secret)
 }
 
-fn sha256(input: &[u8]) -> Vec<u8> {
-    let mut result = [0u8; 32];
-    let mut hasher = Sha256::new();
-    hasher.input(input);
-    hasher.result(&mut result);
-    result.to_vec()
+pub(crate) fn sha256(input: &[u8]) -> Vec<u8> {
+    Sha256::digest(input).to_vec()
 }
 
-pub fn shared_secret_to_chacha<E: PairingEngine>(
+/// Derives the 32-byte symmetric key of a ciphertext from its shared secret
+/// with the KDF of `suite`
+pub fn shared_secret_to_symmetric_key<E: PairingEngine>(
     s: &E::Fqk,
-) -> ChaCha20Poly1305 {
+    suite: CipherSuiteId,
+) -> [u8; 32] {
     let mut prf_key = Vec::new();
     s.write(&mut prf_key).unwrap();
-    let prf_key_32 = sha256(&prf_key);
+    suite.derive_key(&prf_key)
+}
+
+/// In [`AeadMode::KeyCommitting`], checks that `symmetric_key` matches the key commitment
+pub(crate) fn check_key_commitment<E: PairingEngine>(
+    ciphertext: &Ciphertext<E>,
+    symmetric_key: &[u8; 32],
+) -> Result<()> {
+    match &ciphertext.key_commitment {
+        Some(key_commitment)
+            if *key_commitment != commit_to_key(symmetric_key) =>
+        {
+            Err(ThresholdEncryptionError::KeyCommitmentMismatch)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Commits to a symmetric key, see [`AeadMode::KeyCommitting`]
+fn commit_to_key(symmetric_key: &[u8; 32]) -> [u8; 32] {
+    let mut key_commitment = [0u8; 32];
+    key_commitment.copy_from_slice(&sha256(
+        &[KEY_COMMITMENT_DOMAIN, &symmetric_key[..]].concat(),
+    ));
+    key_commitment
+}
+
+const KEY_COMMITMENT_DOMAIN: &[u8] = b"FERVEO_KEY_COMMITMENT";
+
+/// Header (suite id, mode, key commitment) followed by the payload V
+///
+/// The mode byte keeps a key-committing kc || V apart from a standard payload V' = kc || V.
+fn authenticated_payload(
+    suite: CipherSuiteId,
+    key_commitment: Option<&[u8; 32]>,
+    ciphertext: &[u8],
+) -> Vec<u8> {
+    let mut bytes = vec![suite as u8];
+    match key_commitment {
+        Some(key_commitment) => {
+            bytes.push(KEY_COMMITTING_MODE);
+            bytes.extend_from_slice(key_commitment);
+        }
+        None => bytes.push(STANDARD_MODE),
+    }
+    bytes.extend_from_slice(ciphertext);
+    bytes
+}
+
+/// The AEAD of [`CipherSuiteId::ChaCha20Poly1305Sha256`], keyed from a shared secret
+pub fn shared_secret_to_chacha<E: PairingEngine>(
+    s: &E::Fqk,
+) -> ChaCha20Poly1305 {
+    let prf_key_32 = shared_secret_to_symmetric_key::<E>(
+        s,
+        CipherSuiteId::ChaCha20Poly1305Sha256,
+    );
 
     ChaCha20Poly1305::new(GenericArray::from_slice(&prf_key_32))
 }
 
-fn nonce_from_commitment<E: PairingEngine>(commitment: E::G1Affine) -> Nonce {
+/// SHA-256 of U, of which every AEAD takes its nonce as a prefix
+pub(crate) fn nonce_seed_from_commitment<E: PairingEngine>(
+    commitment: E::G1Affine,
+) -> Vec<u8> {
     let mut commitment_bytes = Vec::new();
     commitment
         .serialize_unchecked(&mut commitment_bytes)
         .unwrap();
-    let commitment_hash = sha256(&commitment_bytes);
-    *Nonce::from_slice(&commitment_hash[..12])
+    sha256(&commitment_bytes)
 }
@@ -2,7 +2,12 @@
 
 use crate::*;
 use ark_ec::ProjectiveCurve;
+use std::collections::HashMap;
 
+/// The blinded key shares of the decrypters of `shares`, multiplied by their Lagrange
+/// coefficients at 0, for [`share_combine_fast`]
+///
+/// Works for any number of shares: the sign of N(0) follows the parity of their count.
 pub fn prepare_combine_fast<E: PairingEngine>(
     public_decryption_contexts: &[PublicDecryptionContextFast<E>],
     shares: &[DecryptionShareFast<E>],
@@ -14,6 +19,11 @@ pub fn prepare_combine_fast<E: PairingEngine>(
         // n_0_i = 1 * t^1 * t^2 ...
         n_0 *= public_decryption_contexts[d_i.decrypter_index].lagrange_n_0;
     }
+    // N(0) = (-1)^|domain| * \prod_i omega_i, where one factor of -1 is already
+    // applied to each blinded key share by `multiply_by_omega_inv`
+    if shares.len() % 2 == 1 {
+        n_0 = -n_0;
+    }
     let s = SubproductDomain::<E::Fr>::new(domain);
     let mut lagrange = s.inverse_lagrange_coefficients(); // 1/L_i
 
@@ -21,7 +31,8 @@ pub fn prepare_combine_fast<E: PairingEngine>(
     ark_ff::batch_inversion_and_mul(&mut lagrange, &n_0); // n_0 * L_i
 
     // L_i * [b]Z_i
-    izip!(shares.iter(), lagrange.iter())
+    cfg_iter!(shares)
+        .zip(cfg_iter!(lagrange))
         .map(|(d_i, lambda)| {
             let decrypter = &public_decryption_contexts[d_i.decrypter_index];
             let blinded_key_share =
@@ -42,22 +53,109 @@ pub fn prepare_combine_simple<E: PairingEngine>(
     lagrange_basis_at::<E>(domain, &E::Fr::zero())
 }
 
-/// Calculate lagrange coefficients using optimized formula
+/// Calculate lagrange coefficients at `x_i` for the points `shares_x`
+///
+/// See [`subproductdomain::lagrange_coefficients_at`].
 pub fn lagrange_basis_at<E: PairingEngine>(
     shares_x: &[E::Fr],
     x_i: &E::Fr,
 ) -> Vec<<E>::Fr> {
-    let mut lagrange_coeffs = vec![];
-    for x_j in shares_x {
-        let mut prod = E::Fr::one();
-        for x_m in shares_x {
-            if x_j != x_m {
-                prod *= (*x_m - x_i) / (*x_m - *x_j);
+    subproductdomain::lagrange_coefficients_at(shares_x, x_i)
+}
+
+/// Lagrange coefficients at 0 of the committees combining decryption shares
+///
+/// The same validators usually answer for every ciphertext of a block, so the coefficients
+/// are computed once per committee. Committees are keyed by their sorted decrypter indices,
+/// so the order in which shares arrive does not matter. The cache holds at most `capacity`
+/// committees, and is cleared when a new committee would exceed it.
+#[derive(Clone, Debug)]
+pub struct LagrangeCoeffsCache<E: PairingEngine> {
+    capacity: usize,
+    /// Coefficients in the order of the sorted committee
+    lagrange_coeffs: HashMap<Vec<usize>, Vec<E::Fr>>,
+}
+
+impl<E: PairingEngine> Default for LagrangeCoeffsCache<E> {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl<E: PairingEngine> LagrangeCoeffsCache<E> {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lagrange_coeffs: HashMap::new(),
+        }
+    }
+
+    /// Like [`prepare_combine_simple_committee`], computed on the first use of `committee`
+    /// in any order
+    pub fn get_or_compute(
+        &mut self,
+        pub_contexts: &[PublicDecryptionContextSimple<E>],
+        committee: &[usize],
+    ) -> Result<Vec<E::Fr>> {
+        let mut sorted_committee = committee.to_vec();
+        sorted_committee.sort_unstable();
+        sorted_committee.dedup();
+        if sorted_committee.len() != committee.len() {
+            return Err(ThresholdEncryptionError::InvalidDecrypter);
+        }
+
+        if !self.lagrange_coeffs.contains_key(&sorted_committee) {
+            let lagrange_coeffs = prepare_combine_simple_committee(
+                pub_contexts,
+                &sorted_committee,
+            )?;
+            if self.lagrange_coeffs.len() >= self.capacity {
+                self.lagrange_coeffs.clear();
             }
+            self.lagrange_coeffs
+                .insert(sorted_committee.clone(), lagrange_coeffs);
         }
-        lagrange_coeffs.push(prod);
+        let lagrange_coeffs = &self.lagrange_coeffs[&sorted_committee];
+        Ok(committee
+            .iter()
+            .map(|i| {
+                // Every decrypter is in the sorted committee
+                let position = sorted_committee.binary_search(i).unwrap();
+                lagrange_coeffs[position]
+            })
+            .collect())
+    }
+
+    /// The Lagrange coefficients of the decrypters of `decryption_shares`, in order
+    pub fn get_or_compute_for_shares(
+        &mut self,
+        pub_contexts: &[PublicDecryptionContextSimple<E>],
+        decryption_shares: &[DecryptionShareSimple<E>],
+    ) -> Result<Vec<E::Fr>> {
+        let committee = decryption_shares
+            .iter()
+            .map(|d| d.decrypter_index)
+            .collect::<Vec<_>>();
+        self.get_or_compute(pub_contexts, &committee)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lagrange_coeffs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lagrange_coeffs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lagrange_coeffs.clear()
     }
-    lagrange_coeffs
 }
 
 // TODO: Hide this from external users. Currently blocked by usage in benchmarks.
@@ -100,17 +198,44 @@ pub fn checked_share_combine_fast<E: PairingEngine>(
     Ok(share_combine_fast(decryption_shares, prepared_key_shares))
 }
 
+/// \prod_i C_i^{L_i}, with a multi-exponentiation in GT
+///
+/// Fails if there is not exactly one Lagrange coefficient per decryption share.
 pub fn share_combine_simple<E: PairingEngine>(
     decryption_shares: &[DecryptionShareSimple<E>],
     lagrange_coeffs: &[E::Fr],
-) -> E::Fqk {
-    // Sum of C_i^{L_i}z
-    izip!(decryption_shares, lagrange_coeffs).fold(
-        E::Fqk::one(),
-        |acc, (c_i, alpha_i)| {
-            acc * c_i.decryption_share.pow(alpha_i.into_repr())
-        },
-    )
+) -> Result<E::Fqk>
+where
+    E::Fqk: CyclotomicField,
+{
+    let shares = decryption_shares
+        .iter()
+        .map(|c_i| c_i.decryption_share)
+        .collect::<Vec<_>>();
+    gt_multi_exp(&shares, lagrange_coeffs)
+}
+
+/// Combines the decryption shares of many ciphertexts, all from the decrypters of
+/// `lagrange_coeffs`, in the same order
+///
+/// The exponents are decomposed into windows once for all ciphertexts.
+pub fn share_combine_simple_batch<E: PairingEngine>(
+    decryption_shares: &[Vec<DecryptionShareSimple<E>>],
+    lagrange_coeffs: &[E::Fr],
+) -> Result<Vec<E::Fqk>>
+where
+    E::Fqk: CyclotomicField,
+{
+    let windows = ExponentWindows::new(lagrange_coeffs);
+    cfg_iter!(decryption_shares)
+        .map(|shares| {
+            let shares = shares
+                .iter()
+                .map(|c_i| c_i.decryption_share)
+                .collect::<Vec<_>>();
+            gt_multi_exp_with_windows(&shares, &windows)
+        })
+        .collect()
 }
 
 pub fn share_combine_simple_precomputed<E: PairingEngine>(
@@ -122,6 +247,65 @@ pub fn share_combine_simple_precomputed<E: PairingEngine>(
         .fold(E::Fqk::one(), |acc, c_i| acc * c_i.decryption_share)
 }
 
+/// The Lagrange coefficients at 0 of the decrypters in `committee`, indices into
+/// `pub_contexts`, in the order of `committee`
+pub fn prepare_combine_simple_committee<E: PairingEngine>(
+    pub_contexts: &[PublicDecryptionContextSimple<E>],
+    committee: &[usize],
+) -> Result<Vec<E::Fr>> {
+    let domain = committee
+        .iter()
+        .map(|i| pub_contexts.get(*i).map(|c| c.domain))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ThresholdEncryptionError::CommitteeMismatch)?;
+    Ok(prepare_combine_simple::<E>(&domain))
+}
+
+/// Combines precomputed decryption shares, checking that they come from exactly `committee`
+/// and were created with the Lagrange coefficients of that committee
+pub fn checked_share_combine_simple_precomputed<E: PairingEngine>(
+    pub_contexts: &[PublicDecryptionContextSimple<E>],
+    committee: &[usize],
+    ciphertext: &Ciphertext<E>,
+    decryption_shares: &[DecryptionShareSimplePrecomputed<E>],
+) -> Result<E::Fqk> {
+    let mut sorted_committee = committee.to_vec();
+    sorted_committee.sort_unstable();
+    sorted_committee.dedup();
+    let mut decrypters = decryption_shares
+        .iter()
+        .map(|d| d.decrypter_index)
+        .collect::<Vec<_>>();
+    decrypters.sort_unstable();
+    if sorted_committee.len() != committee.len()
+        || decrypters != sorted_committee
+    {
+        return Err(ThresholdEncryptionError::CommitteeMismatch);
+    }
+
+    let lagrange_coeffs =
+        prepare_combine_simple_committee(pub_contexts, committee)?;
+    let is_valid_shares = decryption_shares.iter().all(|d_i| {
+        let position = committee.iter().position(|i| *i == d_i.decrypter_index);
+        let context = &pub_contexts[d_i.decrypter_index];
+        position.is_some_and(|position| {
+            d_i.verify(
+                &context.blinded_key_share.blinded_key_share,
+                &context.validator_public_key.into_affine(),
+                &context.h.into_projective(),
+                ciphertext,
+                &lagrange_coeffs[position],
+            )
+        })
+    });
+    if !is_valid_shares {
+        return Err(
+            ThresholdEncryptionError::DecryptionShareVerificationFailed,
+        );
+    }
+    Ok(share_combine_simple_precomputed(decryption_shares))
+}
+
 #[cfg(test)]
 mod tests {
     type Fr = <ark_bls12_381::Bls12_381 as ark_ec::PairingEngine>::Fr;
@@ -148,4 +332,40 @@ mod tests {
         let mut lagrange = s.inverse_lagrange_coefficients();
         ark_ff::batch_inversion_and_mul(&mut lagrange, &lagrange_n_0);
     }
+
+    #[test]
+    fn test_lagrange_basis_at_matches_naive() {
+        use ark_std::{One, UniformRand};
+        type E = ark_bls12_381::Bls12_381;
+        let rng = &mut ark_std::test_rng();
+
+        for n in [0, 1, 2, 7, 100] {
+            let shares_x = (0..n).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            for x_i in [Fr::from(0u64), Fr::rand(rng)] {
+                let naive = shares_x
+                    .iter()
+                    .map(|x_j| {
+                        shares_x
+                            .iter()
+                            .filter(|x_m| *x_m != x_j)
+                            .fold(Fr::one(), |acc, x_m| {
+                                acc * (*x_m - x_i) / (*x_m - x_j)
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    super::lagrange_basis_at::<E>(&shares_x, &x_i),
+                    naive
+                );
+            }
+            if n > 0 {
+                let mut expected = vec![Fr::from(0u64); n];
+                expected[n - 1] = Fr::one();
+                assert_eq!(
+                    super::lagrange_basis_at::<E>(&shares_x, &shares_x[n - 1]),
+                    expected
+                );
+            }
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use crate::*;
 use ark_ec::ProjectiveCurve;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Clone, Debug)]
 pub struct PublicDecryptionContextFast<E: PairingEngine> {
@@ -20,7 +22,8 @@ pub struct PublicDecryptionContextSimple<E: PairingEngine> {
     pub validator_public_key: E::G2Projective,
 }
 
-#[derive(Clone, Debug)]
+/// Zeroizes the blinding factors b and b^-1 on drop, and redacts them from `Debug` output
+#[derive(Clone)]
 pub struct SetupParams<E: PairingEngine> {
     pub b: E::Fr,
     pub b_inv: E::Fr,
@@ -30,6 +33,32 @@ pub struct SetupParams<E: PairingEngine> {
     pub h: E::G2Affine,
 }
 
+impl<E: PairingEngine> fmt::Debug for SetupParams<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetupParams")
+            .field("b", &"<redacted>")
+            .field("b_inv", &"<redacted>")
+            .field("g", &self.g)
+            .field("h", &self.h)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: PairingEngine> Zeroize for SetupParams<E> {
+    fn zeroize(&mut self) {
+        self.b.zeroize();
+        self.b_inv.zeroize();
+    }
+}
+
+impl<E: PairingEngine> Drop for SetupParams<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: PairingEngine> ZeroizeOnDrop for SetupParams<E> {}
+
 #[derive(Clone, Debug)]
 pub struct PrivateDecryptionContextFast<E: PairingEngine> {
     pub index: usize,
@@ -38,7 +67,7 @@ pub struct PrivateDecryptionContextFast<E: PairingEngine> {
     pub public_decryption_contexts: Vec<PublicDecryptionContextFast<E>>,
 }
 
-impl<E: PairingEngine> PrivateDecryptionContextFast<E> {
+impl<E: HashToCurve> PrivateDecryptionContextFast<E> {
     pub fn create_share(
         &self,
         ciphertext: &Ciphertext<E>,
@@ -57,9 +86,28 @@ impl<E: PairingEngine> PrivateDecryptionContextFast<E> {
             decryption_share,
         })
     }
+
+    /// Creates decryption shares for every valid ciphertext of a block, skipping the invalid ones
+    pub fn create_share_bundle(
+        &self,
+        ciphertexts: &[Ciphertext<E>],
+        aads: &[&[u8]],
+    ) -> DecryptionShareBundle<E> {
+        let decryption_shares = cfg_iter!(ciphertexts)
+            .zip(cfg_iter!(aads))
+            .filter_map(|(ciphertext, aad)| {
+                self.create_share(ciphertext, aad, &self.setup_params.g_inv)
+                    .ok()
+            })
+            .collect();
+        DecryptionShareBundle {
+            decrypter_index: self.index,
+            decryption_shares,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct PrivateDecryptionContextSimple<E: PairingEngine> {
     pub index: usize,
     pub setup_params: SetupParams<E>,
@@ -69,7 +117,38 @@ pub struct PrivateDecryptionContextSimple<E: PairingEngine> {
     pub validator_private_key: E::Fr,
 }
 
-impl<E: PairingEngine> PrivateDecryptionContextSimple<E> {
+impl<E: PairingEngine> fmt::Debug for PrivateDecryptionContextSimple<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateDecryptionContextSimple")
+            .field("index", &self.index)
+            .field("setup_params", &self.setup_params)
+            .field("private_key_share", &self.private_key_share)
+            .field(
+                "public_decryption_contexts",
+                &self.public_decryption_contexts,
+            )
+            .field("validator_private_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<E: PairingEngine> Zeroize for PrivateDecryptionContextSimple<E> {
+    fn zeroize(&mut self) {
+        self.setup_params.zeroize();
+        self.private_key_share.zeroize();
+        self.validator_private_key.zeroize();
+    }
+}
+
+impl<E: PairingEngine> Drop for PrivateDecryptionContextSimple<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: PairingEngine> ZeroizeOnDrop for PrivateDecryptionContextSimple<E> {}
+
+impl<E: HashToCurve> PrivateDecryptionContextSimple<E> {
     // TODO: Rename to checked_create_share? Or get rid of this "checked_ notation"?
     pub fn create_share(
         &self,
@@ -86,21 +165,46 @@ impl<E: PairingEngine> PrivateDecryptionContextSimple<E> {
         )
     }
 
+    /// Creates decryption shares for a batch of (ciphertext, aad) pairs, see
+    /// [`DecryptionShareSimple::create_batch`]
+    pub fn create_share_batch<R: RngCore>(
+        &self,
+        ciphertexts: &[(&Ciphertext<E>, &[u8])],
+        rng: &mut R,
+    ) -> DecryptionShareSimpleBundle<E> {
+        DecryptionShareSimpleBundle {
+            decrypter_index: self.index,
+            decryption_shares: DecryptionShareSimple::create_batch(
+                self.index,
+                &self.validator_private_key,
+                &self.private_key_share,
+                ciphertexts,
+                &self.setup_params.g_inv,
+                rng,
+            ),
+        }
+    }
+
+    /// Like [`PrivateDecryptionContextSimple::create_share`], with the Lagrange coefficient of
+    /// this decrypter applied, see [`DecryptionShareSimplePrecomputed::create`]
     pub fn create_share_precomputed(
         &self,
         ciphertext: &Ciphertext<E>,
+        aad: &[u8],
         lagrange_coeff: &E::Fr,
-    ) -> DecryptionShareSimplePrecomputed<E> {
-        let u = ciphertext.commitment;
-        // U_{λ_i} = [λ_{i}(0)] U
-        let u_to_lagrange_coeff = u.mul(lagrange_coeff.into_repr());
-        let z_i = self.private_key_share.private_key_share;
-        // C_{λ_i} = e(U_{λ_i}, Z_i)
-        let c_i = E::pairing(u_to_lagrange_coeff, z_i);
-
-        DecryptionShareSimplePrecomputed {
-            decrypter_index: self.index,
-            decryption_share: c_i,
-        }
+    ) -> Result<DecryptionShareSimplePrecomputed<E>> {
+        check_ciphertext_validity::<E>(
+            ciphertext,
+            aad,
+            &self.setup_params.g_inv,
+        )?;
+        // b_inv is the inverse of the validator private key, computed once at setup
+        Ok(DecryptionShareSimplePrecomputed::create_unchecked(
+            self.index,
+            &self.setup_params.b_inv,
+            &self.private_key_share,
+            ciphertext,
+            lagrange_coeff,
+        ))
     }
 }
@@ -22,17 +22,24 @@ impl<E: PairingEngine> DecryptionShareFast<E> {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::try_from_bytes(bytes).unwrap()
+    }
+
+    /// Like [`DecryptionShareFast::from_bytes`], but fails on malformed input instead of panicking
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
         let index_byte_len = 8;
+        if bytes.len() < index_byte_len {
+            return None;
+        }
         let decrypter_index =
-            bincode::deserialize(&bytes[0..index_byte_len]).unwrap();
+            bincode::deserialize(&bytes[0..index_byte_len]).ok()?;
         let decryption_share =
-            CanonicalDeserialize::deserialize(&bytes[index_byte_len..])
-                .unwrap();
+            CanonicalDeserialize::deserialize(&bytes[index_byte_len..]).ok()?;
 
-        DecryptionShareFast {
+        Some(DecryptionShareFast {
             decrypter_index,
             decryption_share,
-        }
+        })
     }
 }
 
@@ -44,7 +51,15 @@ pub struct DecryptionShareSimple<E: PairingEngine> {
     pub validator_checksum: E::G1Affine,
 }
 
-impl<E: PairingEngine> DecryptionShareSimple<E> {
+/// Decryption shares of a single validator for a batch of ciphertexts
+#[derive(Debug)]
+pub struct DecryptionShareSimpleBundle<E: PairingEngine> {
+    pub decrypter_index: usize,
+    /// One result per ciphertext, in batch order, with an error for every invalid ciphertext
+    pub decryption_shares: Vec<Result<DecryptionShareSimple<E>>>,
+}
+
+impl<E: HashToCurve> DecryptionShareSimple<E> {
     pub fn create(
         validator_index: usize,
         validator_decryption_key: &E::Fr,
@@ -54,7 +69,52 @@ impl<E: PairingEngine> DecryptionShareSimple<E> {
         g_inv: &E::G1Prepared,
     ) -> Result<DecryptionShareSimple<E>> {
         check_ciphertext_validity::<E>(ciphertext, aad, g_inv)?;
+        Ok(Self::create_unchecked(
+            validator_index,
+            &validator_decryption_key.inverse().unwrap(),
+            private_key_share,
+            ciphertext,
+        ))
+    }
+
+    /// Like [`DecryptionShareSimple::create`], for a batch of (ciphertext, aad) pairs
+    ///
+    /// Validity of the whole batch is checked with [`check_ciphertext_validity_batch`].
+    /// Returns one result per ciphertext, with an error for every invalid ciphertext.
+    pub fn create_batch<R: RngCore>(
+        validator_index: usize,
+        validator_decryption_key: &E::Fr,
+        private_key_share: &PrivateKeyShare<E>,
+        ciphertexts: &[(&Ciphertext<E>, &[u8])],
+        g_inv: &E::G1Prepared,
+        rng: &mut R,
+    ) -> Vec<Result<DecryptionShareSimple<E>>> {
+        let validator_decryption_key_inv =
+            validator_decryption_key.inverse().unwrap();
+        check_ciphertext_validity_batch(ciphertexts, g_inv, rng)
+            .into_iter()
+            .zip(ciphertexts)
+            .map(|(validity, (ciphertext, _))| {
+                validity.map(|_| {
+                    Self::create_unchecked(
+                        validator_index,
+                        &validator_decryption_key_inv,
+                        private_key_share,
+                        ciphertext,
+                    )
+                })
+            })
+            .collect()
+    }
+}
 
+impl<E: PairingEngine> DecryptionShareSimple<E> {
+    fn create_unchecked(
+        validator_index: usize,
+        validator_decryption_key_inv: &E::Fr,
+        private_key_share: &PrivateKeyShare<E>,
+        ciphertext: &Ciphertext<E>,
+    ) -> DecryptionShareSimple<E> {
         // D_i = e(U, Z_i)
         let decryption_share = E::pairing(
             ciphertext.commitment,
@@ -64,14 +124,14 @@ impl<E: PairingEngine> DecryptionShareSimple<E> {
         // C_i = dk_i^{-1} * U
         let validator_checksum = ciphertext
             .commitment
-            .mul(validator_decryption_key.inverse().unwrap())
+            .mul(*validator_decryption_key_inv)
             .into_affine();
 
-        Ok(DecryptionShareSimple {
+        DecryptionShareSimple {
             decrypter_index: validator_index,
             decryption_share,
             validator_checksum,
-        })
+        }
     }
 
     // TODO: Use public context (validators public state) instead of passing `validator_public_key`
@@ -226,10 +286,88 @@ pub fn verify_decryption_shares_simple<E: PairingEngine>(
     true
 }
 
+/// A simple-variant decryption share with the Lagrange coefficient of its decrypter within
+/// a committee known ahead of time already applied
 #[derive(Debug, Clone)]
 pub struct DecryptionShareSimplePrecomputed<E: PairingEngine> {
     pub decrypter_index: usize,
     pub decryption_share: E::Fqk,
+    /// [λ_i dk_i^-1] U
+    pub validator_checksum: E::G1Affine,
+}
+
+impl<E: HashToCurve> DecryptionShareSimplePrecomputed<E> {
+    pub fn create(
+        validator_index: usize,
+        validator_decryption_key: &E::Fr,
+        private_key_share: &PrivateKeyShare<E>,
+        ciphertext: &Ciphertext<E>,
+        aad: &[u8],
+        lagrange_coeff: &E::Fr,
+        g_inv: &E::G1Prepared,
+    ) -> Result<DecryptionShareSimplePrecomputed<E>> {
+        check_ciphertext_validity::<E>(ciphertext, aad, g_inv)?;
+        Ok(Self::create_unchecked(
+            validator_index,
+            &validator_decryption_key.inverse().unwrap(),
+            private_key_share,
+            ciphertext,
+            lagrange_coeff,
+        ))
+    }
+}
+
+impl<E: PairingEngine> DecryptionShareSimplePrecomputed<E> {
+    pub(crate) fn create_unchecked(
+        validator_index: usize,
+        validator_decryption_key_inv: &E::Fr,
+        private_key_share: &PrivateKeyShare<E>,
+        ciphertext: &Ciphertext<E>,
+        lagrange_coeff: &E::Fr,
+    ) -> DecryptionShareSimplePrecomputed<E> {
+        // U_{λ_i} = [λ_{i}(0)] U
+        let u_to_lagrange_coeff =
+            ciphertext.commitment.mul(lagrange_coeff.into_repr());
+        // C_{λ_i} = e(U_{λ_i}, Z_i)
+        let decryption_share = E::pairing(
+            u_to_lagrange_coeff,
+            private_key_share.private_key_share,
+        );
+        // K_i = [dk_i^{-1}] U_{λ_i}
+        let validator_checksum = u_to_lagrange_coeff
+            .mul(validator_decryption_key_inv.into_repr())
+            .into_affine();
+
+        DecryptionShareSimplePrecomputed {
+            decrypter_index: validator_index,
+            decryption_share,
+            validator_checksum,
+        }
+    }
+
+    /// Like [`DecryptionShareSimple::verify`], for the Lagrange coefficient of the decrypter
+    pub fn verify(
+        &self,
+        share_aggregate: &E::G2Affine,
+        validator_public_key: &E::G2Affine,
+        h: &E::G2Projective,
+        ciphertext: &Ciphertext<E>,
+        lagrange_coeff: &E::Fr,
+    ) -> bool {
+        // C_{λ_i} == e(K_i, Y_i)
+        if self.decryption_share
+            != E::pairing(self.validator_checksum, *share_aggregate)
+        {
+            return false;
+        }
+
+        // e(K_i, ek_i) == e([λ_i] U, H)
+        E::pairing(self.validator_checksum, *validator_public_key)
+            == E::pairing(
+                ciphertext.commitment.mul(lagrange_coeff.into_repr()),
+                *h,
+            )
+    }
 }
 
 #[cfg(test)]
@@ -1,116 +1,519 @@
-#![allow(non_snake_case)]
-#![allow(clippy::many_single_char_names)]
-#![allow(clippy::zero_prefixed_literal)]
-#![allow(dead_code)]
-
-use ark_serialize::CanonicalDeserialize;
-use miracl_core::bls12381::big::BIG;
-use miracl_core::bls12381::dbig::DBIG;
-use miracl_core::bls12381::ecp;
-use miracl_core::bls12381::ecp2::ECP2;
-use miracl_core::bls12381::fp::FP;
-use miracl_core::bls12381::fp2::FP2;
-use miracl_core::bls12381::rom;
-use miracl_core::hmac;
-
-fn ceil(a: usize, b: usize) -> usize {
-    (a - 1) / b + 1
-}
-
-fn hash_to_field2_bls12381(
-    hash: usize,
-    hlen: usize,
-    dst: &[u8],
-    msg: &[u8],
-    ctr: usize,
-) -> [miracl_core::bls12381::fp2::FP2; 2] {
-    let mut u: [FP2; 2] = [FP2::new(), FP2::new()];
-
-    let q = BIG::new_ints(&rom::MODULUS);
-    let k = q.nbits();
-    let r = BIG::new_ints(&rom::CURVE_ORDER);
-    let m = r.nbits();
-    let L = ceil(k + ceil(m, 2), 8);
-    let mut okm: [u8; 512] = [0; 512];
-    hmac::xmd_expand(hash, hlen, &mut okm, 2 * L * ctr, dst, msg);
-    let mut fd: [u8; 256] = [0; 256];
-    for i in 0..ctr {
-        for j in 0..L {
-            fd[j] = okm[2 * i * L + j];
+//! Hashing to the groups of pairing-friendly curves, following RFC 9380
+//! See: https://www.rfc-editor.org/rfc/rfc9380.html
+
+use ark_ec::{
+    short_weierstrass_jacobian::GroupAffine, AffineCurve, PairingEngine,
+    ProjectiveCurve, SWModelParameters,
+};
+use ark_ff::{
+    field_new, BigInteger, Field, Fp2, Fp2Parameters, Fp384, Fp384Parameters,
+    One, PrimeField, SquareRootField, Zero,
+};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+/// Domain separation tag of the hash of a ciphertext header, payload and aad to G2,
+/// the base of the ciphertext tag W
+pub const CIPHERTEXT_TAG_DST: &[u8] = b"FERVEO-V01-TPKE-CIPHERTEXT-TAG";
+
+/// Hashes byte strings to the groups of a pairing engine
+///
+/// Every use must pass its own domain separation tag `dst`, so that distinct protocols
+/// and purposes hash to independent random oracles. Tags longer than 255 bytes are
+/// hashed down as in section 5.3.3 of RFC 9380.
+pub trait HashToCurve: PairingEngine {
+    fn hash_to_g1(msg: &[u8], dst: &[u8]) -> Self::G1Affine;
+    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> Self::G2Affine;
+}
+
+impl HashToCurve for ark_bls12_381::Bls12_381 {
+    /// BLS12381G1_XMD:SHA-256_SSWU_RO_
+    fn hash_to_g1(msg: &[u8], dst: &[u8]) -> ark_bls12_381::G1Affine {
+        bls12_381::hash_to_g1(msg, dst)
+    }
+
+    /// BLS12381G2_XMD:SHA-256_SSWU_RO_
+    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> ark_bls12_381::G2Affine {
+        bls12_381::hash_to_g2(msg, dst)
+    }
+}
+
+impl HashToCurve for ark_bls12_377::Bls12_377 {
+    /// BLS12377G1_XMD:SHA-256_SVDW_RO_
+    fn hash_to_g1(msg: &[u8], dst: &[u8]) -> ark_bls12_377::G1Affine {
+        svdw_hash_to_curve(msg, dst)
+    }
+
+    /// BLS12377G2_XMD:SHA-256_SVDW_RO_
+    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> ark_bls12_377::G2Affine {
+        svdw_hash_to_curve(msg, dst)
+    }
+}
+
+impl SvdwParameters for ark_bls12_377::g1::Parameters {
+    fn svdw_constants() -> &'static SvdwConstants<ark_bls12_377::Fq> {
+        static CONSTANTS: OnceLock<SvdwConstants<ark_bls12_377::Fq>> =
+            OnceLock::new();
+        CONSTANTS.get_or_init(SvdwConstants::new::<Self>)
+    }
+}
+
+impl SvdwParameters for ark_bls12_377::g2::Parameters {
+    fn svdw_constants() -> &'static SvdwConstants<ark_bls12_377::Fq2> {
+        static CONSTANTS: OnceLock<SvdwConstants<ark_bls12_377::Fq2>> =
+            OnceLock::new();
+        CONSTANTS.get_or_init(SvdwConstants::new::<Self>)
+    }
+}
+
+/// `expand_message_xmd` with SHA-256, section 5.3.1
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    const B_IN_BYTES: usize = 32;
+    const S_IN_BYTES: usize = 64;
+    let ell = len.div_ceil(B_IN_BYTES);
+    assert!(ell <= 255 && len <= 65535);
+
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let b_0_xor_b_i = b_0
+            .iter()
+            .zip(b_i.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        b_i = Sha256::new()
+            .chain_update(b_0_xor_b_i)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len);
+    uniform_bytes
+}
+
+/// `hash_to_field` with `expand_message_xmd` and k = 128, section 5.2
+fn hash_to_field<F: Field>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F> {
+    let degree = F::extension_degree() as usize;
+    let l = (F::BasePrimeField::size_in_bits() + 128).div_ceil(8);
+    let uniform_bytes = expand_message_xmd(msg, dst, count * degree * l);
+    uniform_bytes
+        .chunks(degree * l)
+        .map(|element| {
+            let base_elements = element
+                .chunks(l)
+                .map(F::BasePrimeField::from_be_bytes_mod_order)
+                .collect::<Vec<_>>();
+            F::from_base_prime_field_elems(&base_elements).unwrap()
+        })
+        .collect()
+}
+
+/// `sgn0`, section 4.1
+trait Sgn0 {
+    fn sgn0(&self) -> bool;
+}
+
+impl<P: Fp384Parameters> Sgn0 for Fp384<P> {
+    fn sgn0(&self) -> bool {
+        self.into_repr().is_odd()
+    }
+}
+
+impl<P: Fp2Parameters> Sgn0 for Fp2<P>
+where
+    P::Fp: Sgn0,
+{
+    fn sgn0(&self) -> bool {
+        self.c0.sgn0() || (self.c0.is_zero() && self.c1.sgn0())
+    }
+}
+
+/// Flips the sign of `y` to match the sign of `u`
+fn fix_sign<F: Field + Sgn0>(u: &F, y: F) -> F {
+    if u.sgn0() != y.sgn0() {
+        -y
+    } else {
+        y
+    }
+}
+
+/// Simplified SWU map to y^2 = x^3 + a x + b with a, b != 0, section 6.6.2
+fn map_to_curve_sswu<F: SquareRootField + Sgn0>(
+    u: F,
+    a: F,
+    b: F,
+    z: F,
+) -> (F, F) {
+    let g = |x: F| x * (x.square() + a) + b;
+
+    let z_u2 = z * u.square();
+    let tv1 = z_u2.square() + z_u2;
+    let x1 = match tv1.inverse() {
+        Some(tv1) => (-b / a) * (F::one() + tv1),
+        None => b / (z * a),
+    };
+    let (x, y) = match g(x1).sqrt() {
+        Some(y1) => (x1, y1),
+        None => {
+            let x2 = z_u2 * x1;
+            (x2, g(x2).sqrt().unwrap())
         }
-        let mut dx = DBIG::frombytes(&fd[0..L]);
-        let w1 = FP::new_big(&dx.dmod(&q));
+    };
+    (x, fix_sign(&u, y))
+}
+
+/// Evaluates the rational maps of an isogeny, given by their coefficients from the highest
+/// degree down, where the denominators are monic
+fn iso_map<F: Field>(
+    (x, y): (F, F),
+    x_num: &[F],
+    x_den: &[F],
+    y_num: &[F],
+    y_den: &[F],
+) -> Option<(F, F)> {
+    let horner = |monic: bool, coeffs: &[F]| {
+        let leading = if monic { F::one() } else { F::zero() };
+        coeffs.iter().fold(leading, |acc, c| acc * x + c)
+    };
+    let x_den = horner(true, x_den).inverse()?;
+    let y_den = horner(true, y_den).inverse()?;
+    Some((
+        horner(false, x_num) * x_den,
+        y * horner(false, y_num) * y_den,
+    ))
+}
+
+/// The Z of the Shallue-van de Woestijne map, following `find_z_svdw` in appendix H.1
+fn find_z_svdw<P: SWModelParameters>() -> P::BaseField
+where
+    P::BaseField: SquareRootField,
+{
+    let a = P::COEFF_A;
+    let g = |x: P::BaseField| x * (x.square() + a) + P::COEFF_B;
+    let is_square = |x: P::BaseField| x.legendre().is_qr();
+    let two = P::BaseField::from(2u64);
+    let three = P::BaseField::from(3u64);
+    let four = P::BaseField::from(4u64);
+
+    (1u64..)
+        .flat_map(|ctr| {
+            let z = P::BaseField::from(ctr);
+            [z, -z]
+        })
+        .find(|z| {
+            let g_z = g(*z);
+            if g_z.is_zero() {
+                return false;
+            }
+            let h_z = -(three * z.square() + four * a) / (four * g_z);
+            !h_z.is_zero()
+                && is_square(h_z)
+                && (is_square(g_z) || is_square(g(-*z / two)))
+        })
+        .unwrap()
+}
+
+/// The constants Z and c1, ..., c4 of the Shallue-van de Woestijne map, section 6.6.1
+struct SvdwConstants<F> {
+    z: F,
+    c1: F,
+    c2: F,
+    c3: F,
+    c4: F,
+}
 
-        for j in 0..L {
-            fd[j] = okm[(2 * i + 1) * L + j];
+impl<F: SquareRootField + Sgn0> SvdwConstants<F> {
+    fn new<P: SWModelParameters<BaseField = F>>() -> Self {
+        let a = P::COEFF_A;
+        let g = |x: F| x * (x.square() + a) + P::COEFF_B;
+        let z = find_z_svdw::<P>();
+
+        let three_z2_4a = F::from(3u64) * z.square() + F::from(4u64) * a;
+        let mut c3 = (-g(z) * three_z2_4a).sqrt().unwrap();
+        if c3.sgn0() {
+            c3 = -c3;
+        }
+        Self {
+            z,
+            c1: g(z),
+            c2: -z / F::from(2u64),
+            c3,
+            c4: -F::from(4u64) * g(z) / three_z2_4a,
         }
-        dx = DBIG::frombytes(&fd[0..L]);
-        let w2 = FP::new_big(&dx.dmod(&q));
-        u[i].copy(&FP2::new_fps(&w1, &w2));
     }
-    u
 }
 
-pub fn htp_bls12381_g2(msg: &[u8]) -> ark_bls12_381::G2Affine {
-    let dst = "QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_".as_bytes();
-    let u = hash_to_field2_bls12381(hmac::MC_SHA2, ecp::HASH_TYPE, dst, msg, 2);
-    let mut P = ECP2::map2point(&u[0]);
-    let P1 = ECP2::map2point(&u[1]);
-    P.add(&P1);
-    P.cfp();
-    P.affine();
-    /* For arcane reasons, miracl_core uses an extra leading byte,
-    which is always set to either 0x02 or 0x03 for compressed representations,
-    and set to 0x04 for uncompressed representations.
-    miracl_core uses little-endian encoding for Fp2,
-    whereas bls12_381 uses big-endian. */
+/// Curves hashed to with the Shallue-van de Woestijne map. The constants of the map
+/// involve a search for Z and a square root, so they are computed once per curve.
+trait SvdwParameters: SWModelParameters
+where
+    Self::BaseField: SquareRootField + Sgn0,
+{
+    fn svdw_constants() -> &'static SvdwConstants<Self::BaseField>;
+}
 
-    let mut compressed = [0u8; 97];
-    P.tobytes(&mut compressed, true);
+/// Shallue-van de Woestijne map, section 6.6.1
+fn map_to_curve_svdw<P: SvdwParameters>(u: P::BaseField) -> GroupAffine<P>
+where
+    P::BaseField: SquareRootField + Sgn0,
+{
+    type F<P> = <P as ark_ec::ModelParameters>::BaseField;
+    let a = P::COEFF_A;
+    let g = |x: F<P>| x * (x.square() + a) + P::COEFF_B;
+    let one = F::<P>::one();
+    let SvdwConstants { z, c1, c2, c3, c4 } = *P::svdw_constants();
 
-    let mut compressed_rev = [0u8; 96];
-    compressed_rev.clone_from_slice(&compressed[1..]);
-    compressed_rev[000..=047].reverse();
-    compressed_rev[048..=095].reverse();
+    let tv1 = u.square() * c1;
+    let tv2 = one + tv1;
+    let tv1 = one - tv1;
+    let tv3 = (tv1 * tv2).inverse().unwrap_or_else(F::<P>::zero);
+    let tv4 = u * tv1 * tv3 * c3;
+    let x1 = c2 - tv4;
+    let x2 = c2 + tv4;
+    let x3 = z + c4 * (tv2.square() * tv3).square();
 
-    ark_bls12_381::G2Affine::deserialize(&compressed_rev[..]).unwrap()
+    let (x, y) = [x1, x2, x3]
+        .iter()
+        .find_map(|x| g(*x).sqrt().map(|y| (*x, y)))
+        .unwrap();
+    GroupAffine::new(x, fix_sign(&u, y), false)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// `hash_to_curve` with the Shallue-van de Woestijne map and cofactor clearing by
+/// multiplication with the cofactor
+fn svdw_hash_to_curve<P: SvdwParameters>(
+    msg: &[u8],
+    dst: &[u8],
+) -> GroupAffine<P>
+where
+    P::BaseField: SquareRootField + Sgn0,
+{
+    let u = hash_to_field::<P::BaseField>(msg, dst, 2);
+    let q = map_to_curve_svdw::<P>(u[0]).into_projective()
+        + map_to_curve_svdw::<P>(u[1]).into_projective();
+    ProjectiveCurve::mul(q, P::COFACTOR).into_affine()
+}
 
-    fn test_hash_to_g2(msg: &[u8], expected_hex_string: &str) {
-        let mut expected_compressed = [0u8; 96];
-        hex::decode_to_slice(expected_hex_string, &mut expected_compressed)
-            .expect("Failed to decode hex");
+/// BLS12-381 suites of section 8.8
+mod bls12_381 {
+    use super::*;
+    use ark_bls12_381::{
+        Fq, Fq2, G1Affine, G1Projective, G2Affine, G2Projective,
+    };
 
-        let mut expected_compressed_rev = expected_compressed;
-        expected_compressed_rev[0] &= (1 << 5) - 1;
-        expected_compressed_rev.reverse();
+    pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Affine {
+        let u = hash_to_field::<Fq>(msg, dst, 2);
+        let q = map_to_g1(u[0]) + map_to_g1(u[1]);
+        // h_eff = 1 - x
+        ProjectiveCurve::mul(q, [0xd201000000010001]).into_affine()
+    }
 
-        let expected =
-            ark_bls12_381::G2Affine::deserialize(&expected_compressed_rev[..])
-                .unwrap();
+    pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+        let u = hash_to_field::<Fq2>(msg, dst, 2);
+        let q = map_to_g2(u[0]) + map_to_g2(u[1]);
+        ProjectiveCurve::mul(q, G2_H_EFF).into_affine()
+    }
 
-        let res = htp_bls12381_g2(msg);
+    fn map_to_g1(u: Fq) -> G1Projective {
+        let point = map_to_curve_sswu(u, G1_ISO_A, G1_ISO_B, G1_Z);
+        match iso_map(point, &G1_XNUM, &G1_XDEN, &G1_YNUM, &G1_YDEN) {
+            Some((x, y)) => G1Affine::new(x, y, false).into_projective(),
+            None => G1Projective::zero(),
+        }
+    }
 
-        assert!(res == expected)
+    fn map_to_g2(u: Fq2) -> G2Projective {
+        let point = map_to_curve_sswu(u, G2_ISO_A, G2_ISO_B, G2_Z);
+        match iso_map(point, &G2_XNUM, &G2_XDEN, &G2_YNUM, &G2_YDEN) {
+            Some((x, y)) => G2Affine::new(x, y, false).into_projective(),
+            None => G2Projective::zero(),
+        }
     }
 
+    /// h_eff = 3 (x^2 - 1) h_2, in little-endian limbs
+    const G2_H_EFF: [u64; 10] = [
+        0xe8020005aaa95551,
+        0x59894c0adebbf6b4,
+        0xe954cbc06689f6a3,
+        0x2ec0ec69d7477c1a,
+        0x6d82bf015d1212b0,
+        0x329c2f178731db95,
+        0x9986ff031508ffe1,
+        0x88e2a8e9145ad768,
+        0x584c6a0ea91b3528,
+        0x0bc69f08f2ee75b3,
+    ];
+
+    const G1_Z: Fq = field_new!(Fq, "11");
+    const G1_ISO_A: Fq = field_new!(Fq, "12190336318893619529228877361869031420615612348429846051986726275283378313155663745811710833465465981901188123677");
+    const G1_ISO_B: Fq = field_new!(Fq, "2906670324641927570491258158026293881577086121416628140204402091718288198173574630967936031029026176254968826637280");
+
+    // Z = -(2 + i)
+    const G2_Z: Fq2 =
+        field_new!(Fq2, field_new!(Fq, "-2"), field_new!(Fq, "-1"));
+    // A' = 240 i
+    const G2_ISO_A: Fq2 =
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "240"));
+    // B' = 1012 (1 + i)
+    const G2_ISO_B: Fq2 =
+        field_new!(Fq2, field_new!(Fq, "1012"), field_new!(Fq, "1012"));
+
+    // Coefficients of the 11-isogeny map of G1 and 3-isogeny map of G2, appendix E
+    const G1_XNUM: [Fq; 12] = [
+        field_new!(Fq, "1058488477413994682556770863004536636444795456512795473806825292198091015005841418695586811009326456605062948114985"),
+        field_new!(Fq, "2492756312273161536685660027440158956721981129429869601638362407515627529461742974364729223659746272460004902959995"),
+        field_new!(Fq, "3479374185711034293956731583912244564891370843071137483962415222733470401948838363051960066766720884717833231600798"),
+        field_new!(Fq, "1239271775787030039269460763652455868148971086016832054354147730155061349388626624328773377658494412538595239256855"),
+        field_new!(Fq, "3650721292069012982822225637849018828271936405382082649291891245623305084633066170122780668657208923883092359301262"),
+        field_new!(Fq, "2067521456483432583860405634125513059912765526223015704616050604591207046392807563217109432457129564962571408764292"),
+        field_new!(Fq, "3415427104483187489859740871640064348492611444552862448295571438270821994900526625562705192993481400731539293415811"),
+        field_new!(Fq, "2247053637822768981792833880270996398470828564809439728372634811976089874056583714987807553397615562273407692740057"),
+        field_new!(Fq, "3612713941521031012780325893181011392520079402153354595775735142359240110423346445050803899623018402874731133626465"),
+        field_new!(Fq, "2051387046688339481714726479723076305756384619135044672831882917686431912682625619320120082313093891743187631791280"),
+        field_new!(Fq, "3564859427549639835253027846704205725951033235539816243131874237388832081954622352624080767121604606753339903542203"),
+        field_new!(Fq, "2712959285290305970661081772124144179193819192423276218370281158706191519995889425075952244140278856085036081760695"),
+    ];
+    const G1_XDEN: [Fq; 10] = [
+        field_new!(Fq, "1442797143427491432630626390066422021593505165588630398337491100088557278058060064930663878153124164818522816175370"),
+        field_new!(Fq, "1549317016540628014674302140786462938410429359529923207442151939696344988707002602944342203885692366490121021806145"),
+        field_new!(Fq, "3179090966864399634396993677377903383656908036827452986467581478509513058347781039562481806409014718357094150199902"),
+        field_new!(Fq, "1146414465848284837484508420047674663876992808692209238763293935905506532411661921697047880549716175045414621825594"),
+        field_new!(Fq, "2224140216975189437834161136818943039444741035168992629437640302964164227138031844090123490881551522278632040105125"),
+        field_new!(Fq, "3025903087998593826923738290305187197829899948335370692927241015584233559365859980023579293766193297662657497834014"),
+        field_new!(Fq, "501624051089734157816582944025690868317536915684467868346388760435016044027032505306995281054569109955275640941784"),
+        field_new!(Fq, "1717937747208385987946072944131378949849282930538642983149296304709633281382731764122371874602115081850953846504985"),
+        field_new!(Fq, "2822220997908397120956501031591772354860004534930174057793539372552395729721474912921980407622851861692773516917759"),
+        field_new!(Fq, "1353092447850172218905095041059784486169131709710991428415161466575141675351394082965234118340787683181925558786844"),
+    ];
+    const G1_YNUM: [Fq; 16] = [
+        field_new!(Fq, "3370924952219000111210625390420697640496067348723987858345031683392215988129398381698161406651860675722373763741188"),
+        field_new!(Fq, "885704436476567581377743161796735879083481447641210566405057346859953524538988296201011389016649354976986251207243"),
+        field_new!(Fq, "349697005987545415860583335313370109325490073856352967581197273584891698473628451945217286148025358795756956811571"),
+        field_new!(Fq, "1707589313757812493102695021134258021969283151093981498394095062397393499601961942449581422761005023512037430861560"),
+        field_new!(Fq, "3802409194827407598156407709510350851173404795262202653149767739163117554648574333789388883640862266596657730112910"),
+        field_new!(Fq, "3915937073730221072189646057898966011292434045388986394373682715266664498392389619761133407846638689998746172899634"),
+        field_new!(Fq, "2171468288973248519912068884667133903101171670397991979582205855298465414047741472281361964966463442016062407908400"),
+        field_new!(Fq, "1536886493137106337339531461344158973554574987550750910027365237255347020572858445054025958480906372033954157667719"),
+        field_new!(Fq, "1466864076415884313141727877156167508644960317046160398342634861648153052436926062434809922037623519108138661903145"),
+        field_new!(Fq, "718493410301850496156792713845282235942975872282052335612908458061560958159410402177452633054233549648465863759602"),
+        field_new!(Fq, "3443977503653895028417260979421240655844034880950251104724609885224259484262346958661845148165419691583810082940400"),
+        field_new!(Fq, "1353972356724735644398279028378555627591260676383150667237975415318226973994509601413730187583692624416197017403099"),
+        field_new!(Fq, "303251954782077855462083823228569901064301365507057490567314302006681283228886645653148231378803311079384246777035"),
+        field_new!(Fq, "122933100683284845219599644396874530871261396084070222155796123161881094323788483360414289333111221370374027338230"),
+        field_new!(Fq, "2968610969752762946134106091152102846225411740689724909058016729455736597929366401532929068084731548131227395540630"),
+        field_new!(Fq, "1393399195776646641963150658816615410692049723305861307490980409834842911816308830479576739332720113414154429643571"),
+    ];
+    const G1_YDEN: [Fq; 15] = [
+        field_new!(Fq, "2164195715141237148945939585099633032390257748382945597506236650132835917087090097395995817229686247227784224263055"),
+        field_new!(Fq, "369162719928976119195087327055926326601627748362769544198813069133429557026740823593067700396825489145575282378487"),
+        field_new!(Fq, "1668238650112823419388205992952852912407572045257706138925379268508860023191233729074751042562151098884528280913356"),
+        field_new!(Fq, "1662231279858095762833829698537304807741442669992646287950513237989158777254081548205552083108208170765474149568658"),
+        field_new!(Fq, "742483168411032072323733249644347333168432665415341249073150659015707795549260947228694495111018381111866512337576"),
+        field_new!(Fq, "3459661102222301807083870307127272890283709299202626530836335779816726101522661683404130556379097384249447658110805"),
+        field_new!(Fq, "3755735109429418587065437067067640634211015783636675372165599470771975919172394156249639331555277748466603540045130"),
+        field_new!(Fq, "3484671274283470572728732863557945897902920439975203610275006103818288159899345245633896492713412187296754791689945"),
+        field_new!(Fq, "3443845896188810583748698342858554856823966611538932245284665132724280883115455093457486044009395063504744802318172"),
+        field_new!(Fq, "1362317127649143894542621413133849052553333099883364300946623208643344298804722863920546222860227051989127113848748"),
+        field_new!(Fq, "1828256966233331991927609917644344011503610008134915752990581590799656305331275863706710232159635159092657073225757"),
+        field_new!(Fq, "3496628876382137961119423566187258795236027183112131017519536056628828830323846696121917502443333849318934945158166"),
+        field_new!(Fq, "854914566454823955479427412036002165304466268547334760894270240966182605542146252771872707010378658178126128834546"),
+        field_new!(Fq, "3907278185868397906991868466757978732688957419873771881240086730384895060595583602347317992689443299391009456758845"),
+        field_new!(Fq, "3396434800020507717552209507749485772788165484415495716688989613875369612529138640646200921379825018840894888371137"),
+    ];
+    const G2_XNUM: [Fq2; 4] = [
+        field_new!(Fq2, field_new!(Fq, "3557697382419259905260257622876359250272784728834673675850718343221361467102966990615722337003569479144794908942033"), field_new!(Fq, "0")),
+        field_new!(Fq2, field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706526"), field_new!(Fq, "1334136518407222464472596608578634718852294273313002628444019378708010550163612621480895876376338554679298090853261")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706522")),
+        field_new!(Fq2, field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235542"), field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235542")),
+    ];
+    const G2_XDEN: [Fq2; 2] = [
+        field_new!(Fq2, field_new!(Fq, "12"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559775")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559715")),
+    ];
+    const G2_YNUM: [Fq2; 4] = [
+        field_new!(Fq2, field_new!(Fq, "2816510427748580758331037284777117739799287910327449993381818688383577828123182200904113516794492504322962636245776"), field_new!(Fq, "0")),
+        field_new!(Fq2, field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706524"), field_new!(Fq, "1334136518407222464472596608578634718852294273313002628444019378708010550163612621480895876376338554679298090853263")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235518")),
+        field_new!(Fq2, field_new!(Fq, "3261222600550988246488569487636662646083386001431784202863158481286248011511053074731078808919938689216061999863558"), field_new!(Fq, "3261222600550988246488569487636662646083386001431784202863158481286248011511053074731078808919938689216061999863558")),
+    ];
+    const G2_YDEN: [Fq2; 3] = [
+        field_new!(Fq2, field_new!(Fq, "18"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559769")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559571")),
+        field_new!(Fq2, field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559355"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559355")),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
-    fn hash_nothing_g2() {
-        let msg = b"";
-        let expected_hex_string =
-            "a5cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a";
-        test_hash_to_g2(msg, expected_hex_string)
+    fn hash_to_bls12_377() {
+        type E = ark_bls12_377::Bls12_377;
+
+        for msg in [&b""[..], b"abc"] {
+            let g1 = E::hash_to_g1(msg, CIPHERTEXT_TAG_DST);
+            assert!(
+                g1.is_on_curve()
+                    && g1.is_in_correct_subgroup_assuming_on_curve()
+            );
+            assert!(!g1.is_zero());
+
+            let g2 = E::hash_to_g2(msg, CIPHERTEXT_TAG_DST);
+            assert!(
+                g2.is_on_curve()
+                    && g2.is_in_correct_subgroup_assuming_on_curve()
+            );
+            assert!(!g2.is_zero());
+        }
+        assert_ne!(
+            E::hash_to_g2(b"", CIPHERTEXT_TAG_DST),
+            E::hash_to_g2(b"abc", CIPHERTEXT_TAG_DST)
+        );
     }
 
     #[test]
-    fn hash_abc_g2() {
+    fn domain_separation() {
+        type E = ark_bls12_381::Bls12_381;
+
         let msg = b"abc";
-        let expected_hex_string =
-            "939cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd802c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6";
-        test_hash_to_g2(msg, expected_hex_string)
+        assert_ne!(
+            E::hash_to_g2(msg, CIPHERTEXT_TAG_DST),
+            E::hash_to_g2(msg, b"FERVEO-V01-TPKE-TEST")
+        );
+
+        let long_dst = [7u8; 300];
+        assert_eq!(
+            E::hash_to_g1(msg, &long_dst),
+            E::hash_to_g1(msg, &long_dst)
+        );
+        assert_ne!(
+            E::hash_to_g1(msg, &long_dst),
+            E::hash_to_g1(msg, &long_dst[..255])
+        );
     }
 }
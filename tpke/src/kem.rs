@@ -0,0 +1,129 @@
+use crate::*;
+
+/// Encapsulates a fresh 32-byte symmetric key to `pubkey`
+///
+/// Returns the header (U, W), a [`Ciphertext`] with an empty payload V, and the key.
+/// The header is validated with [`check_ciphertext_validity`] and decryption shares are
+/// created for it like for any other ciphertext.
+pub fn encapsulate<R: RngCore, E: HashToCurve>(
+    pubkey: &E::G1Affine,
+    aad: &[u8],
+    rng: &mut R,
+) -> (Ciphertext<E>, [u8; 32]) {
+    encapsulate_with_suite(pubkey, aad, CipherSuiteId::default(), rng)
+}
+
+/// Like [`encapsulate`], with the key derived by the KDF of `suite`
+pub fn encapsulate_with_suite<R: RngCore, E: HashToCurve>(
+    pubkey: &E::G1Affine,
+    aad: &[u8],
+    suite: CipherSuiteId,
+    rng: &mut R,
+) -> (Ciphertext<E>, [u8; 32]) {
+    let (rand_element, commitment, symmetric_key) =
+        encapsulate_symmetric_key::<R, E>(pubkey, suite, rng);
+    // The header commits to the key, so that decapsulation detects a wrong shared secret
+    let header = seal(
+        rand_element,
+        commitment,
+        vec![],
+        &symmetric_key,
+        suite,
+        AeadMode::KeyCommitting,
+        aad,
+    );
+    (header, symmetric_key)
+}
+
+pub fn checked_decapsulate<E: HashToCurve>(
+    header: &Ciphertext<E>,
+    aad: &[u8],
+    g_inv: &E::G1Prepared,
+    privkey: &E::G2Affine,
+) -> Result<[u8; 32]> {
+    let s = E::product_of_pairings(&[(
+        E::G1Prepared::from(header.commitment),
+        E::G2Prepared::from(*privkey),
+    )]);
+    checked_decapsulate_with_shared_secret(header, aad, g_inv, &s)
+}
+
+/// Decapsulates the key of `header` from a shared secret, such as one combined from
+/// decryption shares
+pub fn checked_decapsulate_with_shared_secret<E: HashToCurve>(
+    header: &Ciphertext<E>,
+    aad: &[u8],
+    g_inv: &E::G1Prepared,
+    shared_secret: &E::Fqk,
+) -> Result<[u8; 32]> {
+    check_ciphertext_validity(header, aad, g_inv)?;
+    let symmetric_key =
+        shared_secret_to_symmetric_key::<E>(shared_secret, header.suite);
+    check_key_commitment(header, &symmetric_key)?;
+    Ok(symmetric_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fqk = <E as PairingEngine>::Fqk;
+
+    #[test]
+    fn kem_roundtrip() {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, privkey, contexts) =
+            setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        let (header, key) = encapsulate::<_, E>(&pubkey, aad, rng);
+        assert_eq!(
+            checked_decapsulate(&header, aad, g_inv, &privkey).unwrap(),
+            key
+        );
+        assert!(checked_decapsulate(
+            &header,
+            "bad aad".as_bytes(),
+            g_inv,
+            &privkey
+        )
+        .is_err());
+
+        // Threshold decapsulation
+        let shares = contexts
+            .iter()
+            .map(|ctx| ctx.create_share(&header, aad, g_inv).unwrap())
+            .collect::<Vec<_>>();
+        let prepared = prepare_combine_fast(
+            &contexts[0].public_decryption_contexts,
+            &shares,
+        );
+        let shared_secret = share_combine_fast(&shares, &prepared);
+        assert_eq!(
+            checked_decapsulate_with_shared_secret(
+                &header,
+                aad,
+                g_inv,
+                &shared_secret
+            )
+            .unwrap(),
+            key
+        );
+
+        assert!(matches!(
+            checked_decapsulate_with_shared_secret(
+                &header,
+                aad,
+                g_inv,
+                &Fqk::one()
+            ),
+            Err(ThresholdEncryptionError::KeyCommitmentMismatch)
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::*;
 use ark_ec::ProjectiveCurve;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Debug, Clone)]
 pub struct PublicKeyShare<E: PairingEngine> {
@@ -52,11 +54,34 @@ impl<E: PairingEngine> BlindedKeyShare<E> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A private key share, zeroized on drop and redacted from `Debug` output
+#[derive(Clone, PartialEq)]
 pub struct PrivateKeyShare<E: PairingEngine> {
     pub private_key_share: E::G2Affine,
 }
 
+impl<E: PairingEngine> fmt::Debug for PrivateKeyShare<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateKeyShare")
+            .field("private_key_share", &"<redacted>")
+            .finish()
+    }
+}
+
+impl<E: PairingEngine> Zeroize for PrivateKeyShare<E> {
+    fn zeroize(&mut self) {
+        self.private_key_share.zeroize();
+    }
+}
+
+impl<E: PairingEngine> Drop for PrivateKeyShare<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: PairingEngine> ZeroizeOnDrop for PrivateKeyShare<E> {}
+
 impl<E: PairingEngine> PrivateKeyShare<E> {
     pub fn blind(&self, b: E::Fr) -> BlindedKeyShare<E> {
         let blinding_key =
@@ -1,35 +1,51 @@
-use crate::hash_to_curve::htp_bls12381_g2;
-use crate::SetupParams;
-
 use ark_ec::{AffineCurve, PairingEngine};
 use ark_ff::{Field, One, PrimeField, ToBytes, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Polynomial, UVPolynomial,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::cfg_iter;
 use itertools::izip;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use subproductdomain::{fast_multiexp, SubproductDomain};
 
 use rand_core::RngCore;
-use std::usize;
 
 use thiserror::Error;
 
+mod block;
+mod cipher_suite;
 mod ciphertext;
 mod combine;
 mod context;
 mod decryption;
 mod hash_to_curve;
+mod kem;
 mod key_share;
+mod multiexp;
+mod public_key;
 mod refresh;
+mod share_aggregation;
+mod stream;
+mod transaction;
 
+pub use block::*;
+pub use cipher_suite::*;
 pub use ciphertext::*;
 pub use combine::*;
 pub use context::*;
 pub use decryption::*;
+pub use hash_to_curve::*;
+pub use kem::*;
 pub use key_share::*;
+pub use multiexp::*;
+pub use public_key::*;
 pub use refresh::*;
+pub use share_aggregation::*;
+pub use stream::*;
+pub use transaction::*;
 
 #[cfg(feature = "api")]
 pub mod api;
@@ -59,18 +75,55 @@ pub enum ThresholdEncryptionError {
 
     #[error("plaintext verification failed")]
     PlaintextVerificationFailed,
-}
 
-pub type Result<T> = std::result::Result<T, ThresholdEncryptionError>;
+    /// The symmetric key does not match the key commitment of the ciphertext
+    #[error("key commitment mismatch")]
+    KeyCommitmentMismatch,
+
+    /// Decryption shares of every ciphertext must come from the same decrypters
+    #[error("decryption shares can not be aggregated")]
+    DecryptionShareAggregationFailed,
+
+    /// Precomputed decryption shares must come from exactly the committee they were created for
+    #[error("decryption shares do not match the committee")]
+    CommitteeMismatch,
+
+    /// Fewer valid decryption shares than the threshold
+    #[error("not enough valid decryption shares")]
+    InsufficientDecryptionShares,
+
+    /// A decrypter index is outside the committee or appears more than once
+    #[error("unknown or duplicate decrypter")]
+    InvalidDecrypter,
 
-fn hash_to_g2<T: ark_serialize::CanonicalDeserialize>(message: &[u8]) -> T {
-    let mut point_ser: Vec<u8> = Vec::new();
-    let point = htp_bls12381_g2(message);
-    point.serialize(&mut point_ser).unwrap();
-    T::deserialize(&point_ser[..]).unwrap()
+    /// Every ciphertext needs exactly one additional authenticated data
+    #[error("number of aads does not match the number of ciphertexts")]
+    AadCountMismatch,
+
+    /// Every decryption share needs exactly one Lagrange coefficient
+    #[error("number of decryption shares does not match the number of lagrange coefficients")]
+    LagrangeCoeffCountMismatch,
+
+    /// The decryption data of a block does not match its ciphertexts
+    #[error("block decryption verification failed")]
+    BlockDecryptionVerificationFailed,
+
+    /// The bytes do not encode an encrypted transaction
+    #[error("malformed encrypted transaction")]
+    MalformedTransaction,
+
+    /// The transaction is encrypted to a different epoch
+    #[error("transaction epoch mismatch")]
+    EpochMismatch,
+
+    /// Reading or writing a stream failed
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
 }
 
-fn construct_tag_hash<E: PairingEngine>(
+pub type Result<T> = std::result::Result<T, ThresholdEncryptionError>;
+
+fn construct_tag_hash<E: HashToCurve>(
     u: E::G1Affine,
     stream_ciphertext: &[u8],
     aad: &[u8],
@@ -80,7 +133,7 @@ fn construct_tag_hash<E: PairingEngine>(
     hash_input.extend_from_slice(stream_ciphertext);
     hash_input.extend_from_slice(aad);
 
-    hash_to_g2(&hash_input)
+    E::hash_to_g2(&hash_input, CIPHERTEXT_TAG_DST)
 }
 
 pub fn setup_fast<E: PairingEngine>(
@@ -296,7 +349,8 @@ mod tests {
         let serialized = ciphertext.to_bytes();
         let deserialized: Ciphertext<E> = Ciphertext::from_bytes(&serialized);
 
-        assert_eq!(serialized, deserialized.to_bytes())
+        assert_eq!(serialized, deserialized.to_bytes());
+        assert_eq!(ciphertext.ciphertext, deserialized.ciphertext);
     }
 
     #[test]
@@ -319,7 +373,60 @@ mod tests {
         assert_eq!(msg, plaintext)
     }
 
-    fn test_ciphertext_validation_fails<E: PairingEngine>(
+    #[test]
+    fn key_committing_encryption() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, privkey, contexts) =
+            setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        let ciphertext = encrypt_with_mode::<StdRng, E>(
+            msg,
+            aad,
+            &pubkey,
+            AeadMode::KeyCommitting,
+            rng,
+        );
+        assert_eq!(ciphertext.mode(), AeadMode::KeyCommitting);
+
+        let plaintext =
+            checked_decrypt(&ciphertext, aad, g_inv, &privkey).unwrap();
+        assert_eq!(msg, plaintext);
+
+        let deserialized: Ciphertext<E> =
+            Ciphertext::from_bytes(&ciphertext.to_bytes());
+        assert_eq!(deserialized.key_commitment, ciphertext.key_commitment);
+
+        // Any other key is rejected before the payload is decrypted
+        assert!(matches!(
+            decrypt_with_symmetric_key(&ciphertext, &[0u8; 32]),
+            Err(ThresholdEncryptionError::KeyCommitmentMismatch)
+        ));
+
+        // The mode is covered by the ciphertext tag, so kc || V can not be passed off as the
+        // payload of a standard ciphertext
+        let mut downgraded = ciphertext.clone();
+        downgraded.ciphertext = [
+            &ciphertext.key_commitment.unwrap()[..],
+            &ciphertext.ciphertext[..],
+        ]
+        .concat();
+        downgraded.key_commitment = None;
+        assert_eq!(downgraded.mode(), AeadMode::Standard);
+        assert!(check_ciphertext_validity(&downgraded, aad, g_inv).is_err());
+
+        // The key commitment is covered by the ciphertext tag
+        let mut tampered = ciphertext;
+        tampered.key_commitment = Some([0u8; 32]);
+        assert!(check_ciphertext_validity(&tampered, aad, g_inv).is_err());
+    }
+
+    fn test_ciphertext_validation_fails<E: HashToCurve>(
         msg: &[u8],
         aad: &[u8],
         ciphertext: &Ciphertext<E>,
@@ -382,6 +489,37 @@ mod tests {
         assert!(check_ciphertext_validity(&ciphertext, aad, g_inv).is_err());
     }
 
+    #[test]
+    fn ciphertext_validity_check_batch() {
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let mut ciphertexts = (0..7)
+            .map(|_| encrypt::<StdRng, E>(b"tx", aad, &pubkey, rng))
+            .collect::<Vec<_>>();
+        ciphertexts[2].ciphertext[0] += 1;
+        ciphertexts[6].auth_tag = ciphertexts[5].auth_tag;
+
+        let mut batch = ciphertexts.iter().map(|c| (c, aad)).collect_vec();
+        batch[4].1 = "bad aad".as_bytes();
+
+        let results = check_ciphertext_validity_batch(&batch, g_inv, rng);
+        let invalid = results.iter().positions(|r| r.is_err()).collect_vec();
+        assert_eq!(invalid, vec![2, 4, 6]);
+        for ((ciphertext, aad), result) in batch.iter().zip(&results) {
+            assert_eq!(
+                check_ciphertext_validity(ciphertext, aad, g_inv).is_ok(),
+                result.is_ok()
+            );
+        }
+
+        assert!(
+            check_ciphertext_validity_batch(&batch[..0], g_inv, rng).is_empty()
+        );
+    }
+
     #[test]
     fn fast_decryption_share_validation() {
         let rng = &mut test_rng();
@@ -465,6 +603,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fast_threshold_decryption_with_odd_and_even_share_counts() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let ciphertext = encrypt::<_, E>(msg, aad, &pubkey, rng);
+
+        // N(0) changes sign with the parity of the number of combined shares
+        for num_shares in [threshold, threshold + 1] {
+            let decryption_shares = contexts[..num_shares]
+                .iter()
+                .map(|c| c.create_share(&ciphertext, aad, g_inv).unwrap())
+                .collect::<Vec<_>>();
+            let prepared_blinded_key_shares =
+                prepare_combine_fast(pub_contexts, &decryption_shares);
+            let shared_secret = share_combine_fast(
+                &decryption_shares,
+                &prepared_blinded_key_shares,
+            );
+            let plaintext = checked_decrypt_with_shared_secret(
+                &ciphertext,
+                aad,
+                g_inv,
+                &shared_secret,
+            )
+            .unwrap();
+            assert_eq!(plaintext, msg);
+        }
+    }
+
     #[test]
     fn simple_threshold_decryption() {
         let mut rng = &mut test_rng();
@@ -498,6 +672,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn secret_key_material_is_redacted() {
+        let rng = &mut test_rng();
+        let (_, _, contexts) = setup_simple::<E>(2, 3, rng);
+
+        let debug = format!("{:?}", contexts[0]);
+        assert!(debug.contains("<redacted>"));
+        for secret in [
+            format!("{:?}", contexts[0].setup_params.b),
+            format!("{:?}", contexts[0].setup_params.b_inv),
+            format!("{:?}", contexts[0].validator_private_key),
+            format!("{:?}", contexts[0].private_key_share.private_key_share),
+        ] {
+            assert!(!debug.contains(&secret));
+        }
+
+        let mut context = contexts[0].clone();
+        zeroize::Zeroize::zeroize(&mut context);
+        assert!(context.validator_private_key.is_zero());
+        assert!(context.setup_params.b_inv.is_zero());
+        let zeroized = &context.private_key_share.private_key_share;
+        assert!(zeroized.x.is_zero() && zeroized.y.is_zero());
+    }
+
+    #[test]
+    fn simple_threshold_decryption_bls12_377() {
+        type E = ark_bls12_377::Bls12_377;
+
+        let mut rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, privkey, contexts) =
+            setup_simple::<E>(threshold, shares_num, &mut rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        let ciphertext = encrypt::<_, E>(msg, aad, &pubkey, rng);
+        let ciphertext: Ciphertext<E> =
+            Ciphertext::from_bytes(&ciphertext.to_bytes());
+        assert_eq!(
+            checked_decrypt(&ciphertext, aad, g_inv, &privkey).unwrap(),
+            msg
+        );
+
+        let shared_secret = make_shared_secret_from_contexts(
+            &contexts,
+            &ciphertext,
+            aad,
+            g_inv,
+        );
+        test_ciphertext_validation_fails(
+            msg,
+            aad,
+            &ciphertext,
+            &shared_secret,
+            g_inv,
+        );
+    }
+
+    #[test]
+    fn simple_threshold_decryption_batch() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let msgs = ["tx-0", "tx-1", "tx-2"];
+        let ciphertexts = msgs
+            .iter()
+            .map(|msg| encrypt::<_, E>(msg.as_bytes(), aad, &pubkey, rng))
+            .collect::<Vec<_>>();
+        // The second ciphertext is checked against the wrong aad
+        let batch = [
+            (&ciphertexts[0], aad),
+            (&ciphertexts[1], "bad aad".as_bytes()),
+            (&ciphertexts[2], aad),
+        ];
+
+        let bundles = contexts
+            .iter()
+            .map(|c| c.create_share_batch(&batch, rng))
+            .collect::<Vec<_>>();
+        for bundle in &bundles {
+            assert!(matches!(
+                bundle.decryption_shares[1],
+                Err(ThresholdEncryptionError::CiphertextVerificationFailed)
+            ));
+        }
+
+        for j in [0, 2] {
+            let decryption_shares = bundles
+                .iter()
+                .map(|bundle| {
+                    bundle.decryption_shares[j].as_ref().unwrap().clone()
+                })
+                .collect::<Vec<_>>();
+            // Same shares as created one ciphertext at a time
+            let single =
+                contexts[0].create_share(&ciphertexts[j], aad).unwrap();
+            assert_eq!(
+                decryption_shares[0].decryption_share,
+                single.decryption_share
+            );
+
+            let shared_secret = make_shared_secret(
+                &contexts[0].public_decryption_contexts,
+                &decryption_shares,
+            );
+            let plaintext = checked_decrypt_with_shared_secret(
+                &ciphertexts[j],
+                aad,
+                &contexts[0].setup_params.g_inv,
+                &shared_secret,
+            )
+            .unwrap();
+            assert_eq!(plaintext, msgs[j].as_bytes());
+        }
+    }
+
+    #[test]
+    fn simple_share_combine_batch() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let decryption_shares = (0..3)
+            .map(|_| {
+                let ciphertext = encrypt::<_, E>(b"tx", aad, &pubkey, rng);
+                contexts
+                    .iter()
+                    .map(|c| c.create_share(&ciphertext, aad).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let domain = pub_contexts.iter().map(|c| c.domain).collect::<Vec<_>>();
+        let lagrange = prepare_combine_simple::<E>(&domain);
+
+        // The GT multi-exponentiation matches the naive product of powers
+        let shared_secrets =
+            share_combine_simple_batch::<E>(&decryption_shares, &lagrange)
+                .unwrap();
+        for (shares, shared_secret) in
+            decryption_shares.iter().zip(&shared_secrets)
+        {
+            assert_eq!(
+                *shared_secret,
+                share_combine_simple::<E>(shares, &lagrange).unwrap()
+            );
+            let naive = izip!(shares, &lagrange).fold(
+                <E as PairingEngine>::Fqk::one(),
+                |acc, (c_i, l_i)| {
+                    acc * c_i.decryption_share.pow(l_i.into_repr())
+                },
+            );
+            assert_eq!(*shared_secret, naive);
+        }
+
+        // Every share needs its Lagrange coefficient
+        assert!(matches!(
+            share_combine_simple_batch::<E>(&decryption_shares, &lagrange[1..]),
+            Err(ThresholdEncryptionError::LagrangeCoeffCountMismatch)
+        ));
+    }
+
+    #[test]
+    fn lagrange_coeffs_cache_reuses_committees() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) =
+            setup_simple::<E>(threshold, shares_num, rng);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let domain = pub_contexts.iter().map(|c| c.domain).collect::<Vec<_>>();
+        let lagrange = prepare_combine_simple::<E>(&domain);
+
+        // Two ciphertexts answered by the same committee
+        let mut cache = LagrangeCoeffsCache::<E>::new();
+        for _ in 0..2 {
+            let ciphertext = encrypt::<_, E>(b"tx", aad, &pubkey, rng);
+            let shares = contexts
+                .iter()
+                .map(|c| c.create_share(&ciphertext, aad).unwrap())
+                .collect::<Vec<_>>();
+            let cached = cache
+                .get_or_compute_for_shares(pub_contexts, &shares)
+                .unwrap();
+            assert_eq!(cached, lagrange);
+        }
+        assert_eq!(cache.len(), 1);
+
+        // The same committee in another order is a cache hit, in that order
+        let committee = (0..pub_contexts.len()).rev().collect::<Vec<_>>();
+        let reversed = cache.get_or_compute(pub_contexts, &committee).unwrap();
+        assert_eq!(
+            reversed,
+            lagrange.iter().rev().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(cache.len(), 1);
+        assert!(matches!(
+            cache.get_or_compute(pub_contexts, &[0, 0]),
+            Err(ThresholdEncryptionError::InvalidDecrypter)
+        ));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn lagrange_coeffs_cache_is_cleared_when_full() {
+        let rng = &mut test_rng();
+        let shares_num = 16;
+        let threshold = shares_num * 2 / 3;
+
+        let (_, _, contexts) = setup_simple::<E>(threshold, shares_num, rng);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+        let capacity = LagrangeCoeffsCache::<E>::DEFAULT_CAPACITY;
+        let committees = (0..shares_num)
+            .tuple_combinations()
+            .map(|(i, j)| vec![i, j])
+            .take(capacity + 1)
+            .collect::<Vec<_>>();
+
+        let mut cache = LagrangeCoeffsCache::<E>::new();
+        for committee in &committees[..capacity] {
+            cache.get_or_compute(pub_contexts, committee).unwrap();
+        }
+        assert_eq!(cache.len(), capacity);
+        // A hit does not evict anything
+        cache.get_or_compute(pub_contexts, &committees[0]).unwrap();
+        assert_eq!(cache.len(), capacity);
+
+        // A new committee clears the full cache before it is added
+        cache
+            .get_or_compute(pub_contexts, &committees[capacity])
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+        // and evicted committees are recomputed correctly
+        assert_eq!(
+            cache.get_or_compute(pub_contexts, &committees[0]).unwrap(),
+            prepare_combine_simple_committee(pub_contexts, &committees[0])
+                .unwrap()
+        );
+        assert_eq!(cache.len(), 2);
+
+        let mut small_cache = LagrangeCoeffsCache::<E>::with_capacity(1);
+        small_cache.get_or_compute(pub_contexts, &[0, 1]).unwrap();
+        small_cache.get_or_compute(pub_contexts, &[1, 2]).unwrap();
+        assert_eq!(small_cache.len(), 1);
+    }
+
     #[test]
     fn simple_threshold_decryption_precomputed() {
         let mut rng = &mut test_rng();
@@ -509,25 +941,80 @@ mod tests {
         let (pubkey, _, contexts) =
             setup_simple::<E>(threshold, shares_num, &mut rng);
         let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
         let ciphertext = encrypt::<_, E>(msg, aad, &pubkey, rng);
 
-        let domain = contexts[0]
-            .public_decryption_contexts
-            .iter()
-            .map(|c| c.domain)
-            .collect::<Vec<_>>();
-        let lagrange_coeffs = prepare_combine_simple::<E>(&domain);
+        // Any committee of threshold decrypters
+        let committee =
+            (shares_num - threshold..shares_num).collect::<Vec<_>>();
+        let lagrange_coeffs =
+            prepare_combine_simple_committee(pub_contexts, &committee).unwrap();
 
-        let decryption_shares: Vec<_> = contexts
+        let decryption_shares: Vec<_> = committee
             .iter()
             .zip_eq(lagrange_coeffs.iter())
-            .map(|(context, lagrange_coeff)| {
-                context.create_share_precomputed(&ciphertext, lagrange_coeff)
+            .map(|(i, lagrange_coeff)| {
+                contexts[*i]
+                    .create_share_precomputed(&ciphertext, aad, lagrange_coeff)
+                    .unwrap()
             })
             .collect();
 
         let shared_secret =
             share_combine_simple_precomputed::<E>(&decryption_shares);
+        assert_eq!(
+            checked_share_combine_simple_precomputed(
+                pub_contexts,
+                &committee,
+                &ciphertext,
+                &decryption_shares
+            )
+            .unwrap(),
+            shared_secret
+        );
+
+        // Shares of another committee, or missing a share
+        let other_committee = (0..threshold).collect::<Vec<_>>();
+        assert!(matches!(
+            checked_share_combine_simple_precomputed(
+                pub_contexts,
+                &other_committee,
+                &ciphertext,
+                &decryption_shares
+            ),
+            Err(ThresholdEncryptionError::CommitteeMismatch)
+        ));
+        assert!(matches!(
+            checked_share_combine_simple_precomputed(
+                pub_contexts,
+                &committee,
+                &ciphertext,
+                &decryption_shares[1..]
+            ),
+            Err(ThresholdEncryptionError::CommitteeMismatch)
+        ));
+        // A share created with the Lagrange coefficient of another decrypter
+        let mut bad_shares = decryption_shares.clone();
+        bad_shares[0] = contexts[committee[0]]
+            .create_share_precomputed(&ciphertext, aad, &lagrange_coeffs[1])
+            .unwrap();
+        // Shares are only created for valid ciphertexts
+        assert!(contexts[committee[0]]
+            .create_share_precomputed(
+                &ciphertext,
+                b"bad aad",
+                &lagrange_coeffs[0]
+            )
+            .is_err());
+        assert!(matches!(
+            checked_share_combine_simple_precomputed(
+                pub_contexts,
+                &committee,
+                &ciphertext,
+                &bad_shares
+            ),
+            Err(ThresholdEncryptionError::DecryptionShareVerificationFailed)
+        ));
 
         test_ciphertext_validation_fails(
             msg,
@@ -619,7 +1106,8 @@ mod tests {
             .last()
             .unwrap()
             .domain;
-        let original_private_key_share = selected_participant.private_key_share;
+        let original_private_key_share =
+            selected_participant.private_key_share.clone();
 
         // Remove one participant from the contexts and all nested structures
         let mut remaining_participants = contexts;
@@ -681,12 +1169,15 @@ mod tests {
         assert_eq!(new_private_key_share, original_private_key_share);
     }
 
-    fn make_shared_secret_from_contexts<E: PairingEngine>(
+    fn make_shared_secret_from_contexts<E: HashToCurve>(
         contexts: &[PrivateDecryptionContextSimple<E>],
         ciphertext: &Ciphertext<E>,
         aad: &[u8],
         _g_inv: &E::G1Prepared,
-    ) -> E::Fqk {
+    ) -> E::Fqk
+    where
+        E::Fqk: CyclotomicField,
+    {
         let decryption_shares: Vec<_> = contexts
             .iter()
             .map(|c| c.create_share(ciphertext, aad).unwrap())
@@ -700,10 +1191,13 @@ mod tests {
     fn make_shared_secret<E: PairingEngine>(
         pub_contexts: &[PublicDecryptionContextSimple<E>],
         decryption_shares: &[DecryptionShareSimple<E>],
-    ) -> E::Fqk {
+    ) -> E::Fqk
+    where
+        E::Fqk: CyclotomicField,
+    {
         let domain = pub_contexts.iter().map(|c| c.domain).collect::<Vec<_>>();
         let lagrange = prepare_combine_simple::<E>(&domain);
-        share_combine_simple::<E>(decryption_shares, &lagrange)
+        share_combine_simple::<E>(decryption_shares, &lagrange).unwrap()
     }
 
     /// Ñ parties (where t <= Ñ <= N) jointly execute a "share recovery" algorithm, and the output is 1 new share.
@@ -813,11 +1307,13 @@ mod tests {
             .unwrap(),
         );
 
-        // Creating a shared secret from remaining shares and the recovered one
-        let new_shared_secret = make_shared_secret(
-            &remaining_participants[0].public_decryption_contexts,
-            &decryption_shares,
-        );
+        // Creating a shared secret from remaining shares and the recovered one,
+        // which is at the random point
+        let mut domain_points = domain_points.clone();
+        domain_points.push(x_r);
+        let lagrange = prepare_combine_simple::<E>(&domain_points);
+        let new_shared_secret =
+            share_combine_simple::<E>(&decryption_shares, &lagrange).unwrap();
 
         assert_eq!(old_shared_secret, new_shared_secret);
     }
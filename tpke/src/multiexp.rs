@@ -0,0 +1,228 @@
+use crate::*;
+
+use ark_ff::fields::{Fp12, Fp12Parameters};
+use ark_std::{cfg_into_iter, cfg_iter};
+
+/// The cyclotomic subgroup of Fqk, where the target group GT of a pairing lives
+pub trait CyclotomicField: Field {
+    /// Squares an element of the cyclotomic subgroup, faster than [`Field::square_in_place`]
+    fn cyclotomic_square_in_place(&mut self);
+}
+
+impl<P: Fp12Parameters> CyclotomicField for Fp12<P> {
+    fn cyclotomic_square_in_place(&mut self) {
+        Fp12::cyclotomic_square_in_place(self)
+    }
+}
+
+/// The window decomposition of the exponents of a multi-exponentiation
+///
+/// Only depends on the exponents, so it is computed once when the same exponents are
+/// applied to many sets of bases, such as the Lagrange coefficients of a block's ciphertexts.
+pub struct ExponentWindows {
+    window_size: usize,
+    /// digits[w][i] is the w-th window of the i-th exponent, from the least significant
+    digits: Vec<Vec<usize>>,
+}
+
+/// Below this many bases, the bases are raised to all window digits ahead instead of
+/// being sorted into Pippenger's buckets
+const PIPPENGER_THRESHOLD: usize = 128;
+
+impl ExponentWindows {
+    pub fn new<F: PrimeField>(exponents: &[F]) -> Self {
+        let window_size = if exponents.len() < PIPPENGER_THRESHOLD {
+            4
+        } else {
+            ln_without_floats(exponents.len()) + 2
+        };
+        let reprs = exponents.iter().map(|e| e.into_repr()).collect::<Vec<_>>();
+        let digits = (0..F::size_in_bits())
+            .step_by(window_size)
+            .map(|start| {
+                reprs
+                    .iter()
+                    .map(|repr| window_digit(repr.as_ref(), start, window_size))
+                    .collect()
+            })
+            .collect();
+        Self {
+            window_size,
+            digits,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.digits.first().map_or(0, |d| d.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// \prod_i bases_i^exponents_i, for bases in the cyclotomic subgroup, such as GT elements
+///
+/// Every window costs one multiplication per base, and the windows are combined with
+/// cyclotomic squarings shared by all bases. The window digits are looked up in per-base
+/// tables of powers for few bases, and in Pippenger's buckets for many.
+///
+/// Fails if there is not exactly one exponent per base.
+pub fn gt_multi_exp<G: CyclotomicField, F: PrimeField>(
+    bases: &[G],
+    exponents: &[F],
+) -> Result<G> {
+    gt_multi_exp_with_windows(bases, &ExponentWindows::new(exponents))
+}
+
+/// Like [`gt_multi_exp`], with the window decomposition of the exponents computed ahead
+pub fn gt_multi_exp_with_windows<G: CyclotomicField>(
+    bases: &[G],
+    windows: &ExponentWindows,
+) -> Result<G> {
+    if bases.len() != windows.len() {
+        return Err(ThresholdEncryptionError::LagrangeCoeffCountMismatch);
+    }
+    if bases.len() < PIPPENGER_THRESHOLD {
+        Ok(interleaved_multi_exp(bases, windows))
+    } else {
+        Ok(pippenger_multi_exp(bases, windows))
+    }
+}
+
+fn interleaved_multi_exp<G: CyclotomicField>(
+    bases: &[G],
+    windows: &ExponentWindows,
+) -> G {
+    let window_size = windows.window_size;
+
+    // tables[i][d - 1] = bases_i^d
+    let tables = cfg_iter!(bases)
+        .map(|base| {
+            let mut table = Vec::with_capacity((1 << window_size) - 1);
+            table.push(*base);
+            for d in 1..(1 << window_size) - 1 {
+                table.push(table[d - 1] * base);
+            }
+            table
+        })
+        .collect::<Vec<_>>();
+
+    windows
+        .digits
+        .iter()
+        .rev()
+        .fold(G::one(), |mut acc, digits| {
+            for _ in 0..window_size {
+                acc.cyclotomic_square_in_place();
+            }
+            for (table, digit) in tables.iter().zip(digits) {
+                if *digit != 0 {
+                    acc *= table[*digit - 1];
+                }
+            }
+            acc
+        })
+}
+
+fn pippenger_multi_exp<G: CyclotomicField>(
+    bases: &[G],
+    windows: &ExponentWindows,
+) -> G {
+    let window_size = windows.window_size;
+
+    // \prod_i bases_i^digit_i for every window
+    let window_products = cfg_into_iter!(0..windows.digits.len())
+        .map(|w| {
+            let mut buckets = vec![G::one(); (1 << window_size) - 1];
+            for (base, digit) in bases.iter().zip(&windows.digits[w]) {
+                if *digit != 0 {
+                    buckets[*digit - 1] *= base;
+                }
+            }
+            // \prod_j buckets_j^(j + 1), as a running product
+            let mut running_product = G::one();
+            let mut window_product = G::one();
+            for bucket in buckets.iter().rev() {
+                running_product *= bucket;
+                window_product *= running_product;
+            }
+            window_product
+        })
+        .collect::<Vec<_>>();
+
+    // From the most significant window, raise to 2^window_size and multiply in the next
+    window_products
+        .iter()
+        .rev()
+        .fold(G::one(), |mut acc, window_product| {
+            for _ in 0..window_size {
+                acc.cyclotomic_square_in_place();
+            }
+            acc * window_product
+        })
+}
+
+/// The `window_size` bits of `limbs`, a little-endian integer, from bit `start`
+fn window_digit(limbs: &[u64], start: usize, window_size: usize) -> usize {
+    let limb = start / 64;
+    let offset = start % 64;
+    let mut digit = limbs[limb] >> offset;
+    if offset + window_size > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - offset);
+    }
+    (digit & ((1 << window_size) - 1)) as usize
+}
+
+/// Approximation of ln(n), as in arkworks' variable-base MSM
+fn ln_without_floats(n: usize) -> usize {
+    // log2(n) * ln(2)
+    (ark_std::log2(n) * 69 / 100) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as PairingEngine>::Fr;
+
+    #[test]
+    fn gt_multi_exp_matches_naive() {
+        let rng = &mut test_rng();
+        for n in [0, 1, 5, 40, 130] {
+            let bases = (0..n)
+                .map(|_| {
+                    E::pairing(
+                        <E as PairingEngine>::G1Affine::prime_subgroup_generator()
+                            .mul(Fr::rand(rng)),
+                        <E as PairingEngine>::G2Affine::prime_subgroup_generator(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut exponents =
+                (0..n).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            if n > 1 {
+                exponents[0] = Fr::zero();
+                exponents[1] = -Fr::one();
+            }
+
+            let naive = bases
+                .iter()
+                .zip(&exponents)
+                .fold(<E as PairingEngine>::Fqk::one(), |acc, (b, e)| {
+                    acc * b.pow(e.into_repr())
+                });
+            assert_eq!(gt_multi_exp(&bases, &exponents).unwrap(), naive);
+        }
+    }
+
+    #[test]
+    fn gt_multi_exp_rejects_length_mismatch() {
+        let bases = vec![<E as PairingEngine>::Fqk::one(); 3];
+        let exponents = vec![Fr::one(); 2];
+        assert!(gt_multi_exp(&bases, &exponents).is_err());
+        assert!(gt_multi_exp(&bases[..2], &exponents).is_ok());
+    }
+}
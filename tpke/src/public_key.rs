@@ -0,0 +1,133 @@
+use crate::*;
+
+use ark_ec::{msm::FixedBaseMSM, ProjectiveCurve};
+
+/// Window size of the fixed-base multiplication table of the generator g
+const G_TABLE_WINDOW: usize = 8;
+
+/// A public key prepared for encrypting many messages
+///
+/// Caches the pairing e(Y, H) and a fixed-base multiplication table for g, so that
+/// encryption takes one GT exponentiation and one fixed-base G1 multiplication instead
+/// of a full pairing.
+#[derive(Clone, Debug)]
+pub struct PreparedPublicKey<E: PairingEngine> {
+    pub public_key: E::G1Affine,
+    /// e(Y, H)
+    pub public_key_pairing: E::Fqk,
+    g_table: Vec<Vec<E::G1Affine>>,
+}
+
+impl<E: PairingEngine> PreparedPublicKey<E> {
+    pub fn new(public_key: &E::G1Affine) -> Self {
+        let h_gen = E::G2Affine::prime_subgroup_generator();
+        let g_table = FixedBaseMSM::get_window_table(
+            Self::scalar_size(),
+            G_TABLE_WINDOW,
+            E::G1Projective::prime_subgroup_generator(),
+        );
+        Self {
+            public_key: *public_key,
+            public_key_pairing: E::pairing(*public_key, h_gen),
+            g_table,
+        }
+    }
+
+    fn scalar_size() -> usize {
+        E::Fr::size_in_bits()
+    }
+
+    /// Like [`encapsulate_symmetric_key`], with s = e(Y, H)^r
+    pub(crate) fn encapsulate_symmetric_key<R: RngCore>(
+        &self,
+        suite: CipherSuiteId,
+        rng: &mut R,
+    ) -> (E::Fr, E::G1Affine, [u8; 32]) {
+        // r
+        let rand_element = E::Fr::rand(rng);
+        // s
+        let product = self.public_key_pairing.pow(rand_element.into_repr());
+        // u
+        let outerc = Self::scalar_size().div_ceil(G_TABLE_WINDOW);
+        let commitment = FixedBaseMSM::windowed_mul::<E::G1Projective>(
+            outerc,
+            G_TABLE_WINDOW,
+            &self.g_table,
+            &rand_element,
+        )
+        .into_affine();
+
+        let symmetric_key =
+            shared_secret_to_symmetric_key::<E>(&product, suite);
+        (rand_element, commitment, symmetric_key)
+    }
+}
+
+impl<E: HashToCurve> PreparedPublicKey<E> {
+    /// Like [`encrypt`], to the prepared public key
+    pub fn encrypt<R: RngCore>(
+        &self,
+        message: &[u8],
+        aad: &[u8],
+        rng: &mut R,
+    ) -> Ciphertext<E> {
+        self.encrypt_with_suite(
+            message,
+            aad,
+            CipherSuiteId::default(),
+            AeadMode::Standard,
+            rng,
+        )
+    }
+
+    /// Like [`encrypt_with_suite`], to the prepared public key
+    pub fn encrypt_with_suite<R: RngCore>(
+        &self,
+        message: &[u8],
+        aad: &[u8],
+        suite: CipherSuiteId,
+        mode: AeadMode,
+        rng: &mut R,
+    ) -> Ciphertext<E> {
+        let (rand_element, commitment, symmetric_key) =
+            self.encapsulate_symmetric_key(suite, rng);
+        encrypt_payload(
+            rand_element,
+            commitment,
+            &symmetric_key,
+            message,
+            suite,
+            mode,
+            aad,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn prepared_public_key_encryption() {
+        let rng = &mut test_rng();
+        let msg: &[u8] = "abc".as_bytes();
+        let aad: &[u8] = "my-aad".as_bytes();
+
+        let (pubkey, privkey, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let prepared = PreparedPublicKey::<E>::new(&pubkey);
+
+        let ciphertext = prepared.encrypt(msg, aad, &mut rng.clone());
+        assert_eq!(
+            checked_decrypt(&ciphertext, aad, g_inv, &privkey).unwrap(),
+            msg
+        );
+
+        // Same randomness, same ciphertext as without preparation
+        let unprepared = encrypt::<_, E>(msg, aad, &pubkey, rng);
+        assert_eq!(ciphertext.to_bytes(), unprepared.to_bytes());
+    }
+}
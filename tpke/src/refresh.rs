@@ -1,11 +1,9 @@
-use crate::{lagrange_basis_at, PrivateKeyShare};
+use crate::PrivateKeyShare;
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{PrimeField, Zero};
 use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
-use itertools::zip_eq;
 use rand_core::RngCore;
-
-use std::usize;
+use subproductdomain::SubproductDomain;
 
 /// From PSS paper, section 4.2.1, (https://link.springer.com/content/pdf/10.1007/3-540-44750-4_27.pdf)
 pub fn prepare_share_updates_for_recovery<E: PairingEngine>(
@@ -50,14 +48,14 @@ pub fn recover_share_from_updated_private_shares<E: PairingEngine>(
     updated_private_shares: &[PrivateKeyShare<E>],
 ) -> PrivateKeyShare<E> {
     // Interpolate new shares to recover y_r
-    let lagrange = lagrange_basis_at::<E>(domain_points, x_r);
-    let prods = zip_eq(updated_private_shares, lagrange)
-        .map(|(y_j, l)| y_j.private_key_share.mul(l.into_repr()));
-    let y_r = prods.fold(E::G2Projective::zero(), |acc, y_j| acc + y_j);
+    let shares = updated_private_shares
+        .iter()
+        .map(|y_j| y_j.private_key_share)
+        .collect::<Vec<_>>();
+    let private_key_share = SubproductDomain::new(domain_points.to_vec())
+        .interpolate_at_in_exponent(&shares, x_r);
 
-    PrivateKeyShare {
-        private_key_share: y_r.into_affine(),
-    }
+    PrivateKeyShare { private_key_share }
 }
 
 pub fn make_random_polynomial_at<E: PairingEngine>(
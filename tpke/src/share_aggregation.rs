@@ -0,0 +1,472 @@
+use crate::*;
+use ark_ec::ProjectiveCurve;
+
+/// Decryption shares of many ciphertexts, aggregated into one share per decrypter.
+/// See `TPKE.AggregateDecryptionShares` in the Ferveo book.
+#[derive(Debug, Clone)]
+pub struct AggregatedDecryptionShares<E: PairingEngine> {
+    /// \hat{D}_i = \sum_j \rho_j D_{i,j}, one per decrypter
+    pub shares: Vec<DecryptionShareFast<E>>,
+}
+
+impl<E: PairingEngine> AggregatedDecryptionShares<E> {
+    const LENGTH_LEN: usize = 8;
+
+    fn share_len() -> usize {
+        // Decrypter index followed by a compressed G1 point
+        8 + E::G1Affine::zero().serialized_size()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = bincode::serialize(&self.shares.len()).unwrap();
+        for share in self.shares.iter() {
+            bytes.extend(share.to_bytes());
+        }
+        bytes
+    }
+
+    /// Reads aggregated shares written by [`AggregatedDecryptionShares::to_bytes`]
+    ///
+    /// Returns `None` if `bytes` is truncated, too long or holds a malformed share.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::LENGTH_LEN {
+            return None;
+        }
+        let (length_bytes, share_bytes) = bytes.split_at(Self::LENGTH_LEN);
+        let shares_num: usize = bincode::deserialize(length_bytes).ok()?;
+        if shares_num.checked_mul(Self::share_len())? != share_bytes.len() {
+            return None;
+        }
+        let shares = share_bytes
+            .chunks(Self::share_len())
+            .map(DecryptionShareFast::try_from_bytes)
+            .collect::<Option<_>>()?;
+        Some(Self { shares })
+    }
+}
+
+/// Computes the publicly known coefficients
+/// \rho_j = H(U_1, S_1, aad_1, ..., U_k, S_k, aad_k, j)
+///
+/// The claimed shared secrets S_j are part of the hash, so that they can not be chosen after
+/// the coefficients are known.
+pub fn aggregation_coefficients<E: PairingEngine>(
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    shared_secrets: &[E::Fqk],
+) -> Vec<E::Fr> {
+    let mut hash_input = Vec::<u8>::new();
+    for (c, aad, s) in izip!(ciphertexts, aads, shared_secrets) {
+        c.commitment.write(&mut hash_input).unwrap();
+        s.write(&mut hash_input).unwrap();
+        hash_input.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+        hash_input.extend_from_slice(aad);
+    }
+    (0..ciphertexts.len() as u64)
+        .map(|j| {
+            let mut rho_input = hash_input.clone();
+            rho_input.extend_from_slice(&j.to_le_bytes());
+            // Hash to 512 bits so that the reduction into Fr is unbiased
+            let mut wide = sha256(&[&rho_input[..], &[0u8]].concat());
+            wide.extend(sha256(&[&rho_input[..], &[1u8]].concat()));
+            E::Fr::from_le_bytes_mod_order(&wide)
+        })
+        .collect()
+}
+
+/// Implements `TPKE.AggregateDecryptionShares`
+///
+/// `decryption_shares[j]` are the shares of ciphertext `j`, and every ciphertext must be
+/// decrypted by the same decrypters in the same order. `aads[j]` and `shared_secrets[j]` are
+/// the additional authenticated data and the combined shared secret of ciphertext `j`.
+pub fn aggregate_decryption_shares<E: PairingEngine>(
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    decryption_shares: &[Vec<DecryptionShareFast<E>>],
+    shared_secrets: &[E::Fqk],
+) -> Result<AggregatedDecryptionShares<E>> {
+    if ciphertexts.is_empty()
+        || ciphertexts.len() != aads.len()
+        || ciphertexts.len() != decryption_shares.len()
+        || ciphertexts.len() != shared_secrets.len()
+    {
+        return Err(ThresholdEncryptionError::DecryptionShareAggregationFailed);
+    }
+    let decrypters = decryption_shares[0]
+        .iter()
+        .map(|d| d.decrypter_index)
+        .collect::<Vec<_>>();
+    let same_decrypters = decryption_shares.iter().all(|shares_j| {
+        shares_j.len() == decrypters.len()
+            && izip!(shares_j, decrypters.iter())
+                .all(|(d, index)| d.decrypter_index == *index)
+    });
+    if !same_decrypters {
+        return Err(ThresholdEncryptionError::DecryptionShareAggregationFailed);
+    }
+
+    let rho = aggregation_coefficients(ciphertexts, aads, shared_secrets);
+
+    // \hat{D}_i = \sum_j \rho_j D_{i,j}
+    let mut aggregated = vec![E::G1Projective::zero(); decrypters.len()];
+    for (shares_j, rho_j) in izip!(decryption_shares, rho.iter()) {
+        for (d_i, d_ij) in izip!(aggregated.iter_mut(), shares_j.iter()) {
+            *d_i += d_ij.decryption_share.mul(*rho_j);
+        }
+    }
+    let aggregated =
+        E::G1Projective::batch_normalization_into_affine(&aggregated);
+
+    Ok(AggregatedDecryptionShares {
+        shares: izip!(decrypters, aggregated)
+            .map(|(decrypter_index, decryption_share)| DecryptionShareFast {
+                decrypter_index,
+                decryption_share,
+            })
+            .collect(),
+    })
+}
+
+/// Implements `TPKE.VerifyAggregatedDecryptionShares`
+///
+/// Checks e(\hat{D}_i, [b_i] H) = e(\sum_j [\rho_j] U_j, H) for every decrypter
+pub fn verify_aggregated_decryption_shares<E: PairingEngine>(
+    pub_contexts: &[PublicDecryptionContextFast<E>],
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    shared_secrets: &[E::Fqk],
+    aggregated: &AggregatedDecryptionShares<E>,
+) -> bool {
+    if ciphertexts.len() != aads.len()
+        || ciphertexts.len() != shared_secrets.len()
+    {
+        return false;
+    }
+    let h_inv = match pub_contexts.first() {
+        Some(pub_context) => pub_context.h_inv.clone(),
+        None => return false,
+    };
+    let rho = aggregation_coefficients(ciphertexts, aads, shared_secrets);
+
+    // \sum_j [\rho_j] U_j
+    let aggregated_commitment = izip!(ciphertexts, rho.iter())
+        .map(|(c, rho_j)| c.commitment.mul(*rho_j))
+        .sum::<E::G1Projective>()
+        .into_affine();
+
+    // e(\sum_j [\rho_j] U_j, -H)
+    let pairing_a = (E::G1Prepared::from(aggregated_commitment), h_inv);
+
+    aggregated.shares.iter().all(|d_i| {
+        let pub_context = match pub_contexts.get(d_i.decrypter_index) {
+            Some(pub_context) => pub_context,
+            None => return false,
+        };
+        // e(\hat{D}_i, [b_i] H)
+        let pairing_b = (
+            E::G1Prepared::from(d_i.decryption_share),
+            pub_context.blinded_key_share.blinding_key_prepared.clone(),
+        );
+        E::product_of_pairings(&[pairing_a.clone(), pairing_b]) == E::Fqk::one()
+    })
+}
+
+/// Implements `TPKE.VerifyAggregatedCombination`
+///
+/// Checks that the shared secrets `shared_secrets[j]` of the ciphertexts are consistent with
+/// the aggregated decryption shares, i.e. \prod_i e(\hat{D}_i, L_i [b]Z_i) = \prod_j S_j^{\rho_j}
+pub fn verify_aggregated_combination<E: PairingEngine>(
+    pub_contexts: &[PublicDecryptionContextFast<E>],
+    ciphertexts: &[Ciphertext<E>],
+    aads: &[&[u8]],
+    shared_secrets: &[E::Fqk],
+    aggregated: &AggregatedDecryptionShares<E>,
+) -> bool {
+    if ciphertexts.len() != aads.len()
+        || ciphertexts.len() != shared_secrets.len()
+        || aggregated
+            .shares
+            .iter()
+            .any(|d_i| d_i.decrypter_index >= pub_contexts.len())
+    {
+        return false;
+    }
+    let rho = aggregation_coefficients(ciphertexts, aads, shared_secrets);
+
+    let prepared_key_shares =
+        prepare_combine_fast(pub_contexts, &aggregated.shares);
+    let aggregated_shared_secret =
+        share_combine_fast(&aggregated.shares, &prepared_key_shares);
+
+    let expected = izip!(shared_secrets, rho.iter())
+        .fold(E::Fqk::one(), |acc, (s_j, rho_j)| {
+            acc * s_j.pow(rho_j.into_repr())
+        });
+
+    aggregated_shared_secret == expected
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fqk = <E as PairingEngine>::Fqk;
+
+    struct TestBlock {
+        contexts: Vec<PrivateDecryptionContextFast<E>>,
+        ciphertexts: Vec<Ciphertext<E>>,
+        aads: Vec<&'static [u8]>,
+        decryption_shares: Vec<Vec<DecryptionShareFast<E>>>,
+        shared_secrets: Vec<Fqk>,
+    }
+
+    fn setup_block(num_ciphertexts: usize) -> TestBlock {
+        let rng = &mut test_rng();
+        let shares_num = 8;
+        let threshold = shares_num * 2 / 3;
+        let aad: &'static [u8] = "my-aad".as_bytes();
+
+        let (pubkey, _, contexts) = setup_fast::<E>(threshold, shares_num, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+
+        let ciphertexts = (0..num_ciphertexts)
+            .map(|j| {
+                let msg = format!("tx-{}", j);
+                encrypt::<_, E>(msg.as_bytes(), aad, &pubkey, rng)
+            })
+            .collect::<Vec<_>>();
+        let decryption_shares = ciphertexts
+            .iter()
+            .map(|c| {
+                contexts
+                    .iter()
+                    .map(|ctx| ctx.create_share(c, aad, g_inv).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let shared_secrets = decryption_shares
+            .iter()
+            .map(|shares| {
+                let prepared = prepare_combine_fast(pub_contexts, shares);
+                share_combine_fast(shares, &prepared)
+            })
+            .collect::<Vec<_>>();
+
+        TestBlock {
+            contexts,
+            aads: vec![aad; ciphertexts.len()],
+            ciphertexts,
+            decryption_shares,
+            shared_secrets,
+        }
+    }
+
+    #[test]
+    fn aggregated_decryption_shares_verify() {
+        let TestBlock {
+            contexts,
+            ciphertexts,
+            aads,
+            decryption_shares,
+            shared_secrets,
+        } = setup_block(4);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+
+        let aggregated = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &shared_secrets,
+        )
+        .unwrap();
+        assert_eq!(aggregated.shares.len(), contexts.len());
+
+        assert!(verify_aggregated_decryption_shares(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &shared_secrets,
+            &aggregated,
+        ));
+        assert!(verify_aggregated_combination(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &shared_secrets,
+            &aggregated,
+        ));
+
+        // A tampered shared secret is detected
+        let mut bad_shared_secrets = shared_secrets;
+        bad_shared_secrets[1] = Fqk::one();
+        assert!(!verify_aggregated_combination(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &bad_shared_secrets,
+            &aggregated,
+        ));
+
+        // A tampered aggregated share is detected
+        let mut bad_aggregated = aggregated;
+        bad_aggregated.shares[0].decryption_share = ciphertexts[0].commitment;
+        assert!(!verify_aggregated_decryption_shares(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &bad_shared_secrets,
+            &bad_aggregated,
+        ));
+    }
+
+    #[test]
+    fn aggregated_combination_rejects_compensating_shared_secrets() {
+        let rng = &mut test_rng();
+        let TestBlock {
+            contexts,
+            ciphertexts,
+            aads,
+            decryption_shares,
+            shared_secrets,
+        } = setup_block(2);
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+
+        // S'_1 = S_1 X^{\rho_2} and S'_2 = S_2 X^{-\rho_1} leave \prod_j S_j^{\rho_j}
+        // unchanged for the coefficients of the honest shared secrets
+        let rho =
+            aggregation_coefficients(&ciphertexts, &aads, &shared_secrets);
+        let x = Fqk::rand(rng);
+        let forged_shared_secrets = vec![
+            shared_secrets[0] * x.pow(rho[1].into_repr()),
+            shared_secrets[1] * x.pow(rho[0].into_repr()).inverse().unwrap(),
+        ];
+        let product = |secrets: &[Fqk]| {
+            izip!(secrets, rho.iter())
+                .fold(Fqk::one(), |acc, (s, r)| acc * s.pow(r.into_repr()))
+        };
+        assert_eq!(product(&shared_secrets), product(&forged_shared_secrets));
+
+        // The coefficients depend on the claimed shared secrets, so the forgery is detected
+        let aggregated = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &forged_shared_secrets,
+        )
+        .unwrap();
+        assert!(verify_aggregated_decryption_shares(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &forged_shared_secrets,
+            &aggregated,
+        ));
+        assert!(!verify_aggregated_combination(
+            pub_contexts,
+            &ciphertexts,
+            &aads,
+            &forged_shared_secrets,
+            &aggregated,
+        ));
+    }
+
+    #[test]
+    fn aggregation_rejects_mismatched_decrypters() {
+        let TestBlock {
+            ciphertexts,
+            aads,
+            mut decryption_shares,
+            shared_secrets,
+            ..
+        } = setup_block(2);
+        decryption_shares[1].pop();
+        assert!(aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &shared_secrets,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn aggregated_decryption_shares_serialization() {
+        let TestBlock {
+            ciphertexts,
+            aads,
+            decryption_shares,
+            shared_secrets,
+            ..
+        } = setup_block(2);
+        let aggregated = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &shared_secrets,
+        )
+        .unwrap();
+
+        let serialized = aggregated.to_bytes();
+        let deserialized: AggregatedDecryptionShares<E> =
+            AggregatedDecryptionShares::from_bytes(&serialized).unwrap();
+        assert_eq!(serialized, deserialized.to_bytes());
+
+        // Malformed input read back from storage is rejected
+        let from_bytes = AggregatedDecryptionShares::<E>::from_bytes;
+        assert!(from_bytes(&[]).is_none());
+        assert!(from_bytes(&serialized[..4]).is_none());
+        assert!(from_bytes(&serialized[..serialized.len() - 1]).is_none());
+        assert!(from_bytes(&[&serialized[..], &[0u8]].concat()).is_none());
+        let mut bad_length = serialized.clone();
+        bad_length[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(from_bytes(&bad_length).is_none());
+        let mut bad_point = serialized;
+        let last = bad_point.len() - 1;
+        bad_point[8 + 8..last].iter_mut().for_each(|b| *b = 0xff);
+        assert!(from_bytes(&bad_point).is_none());
+    }
+
+    #[test]
+    fn aggregated_verification_rejects_empty_or_unknown_contexts() {
+        let TestBlock {
+            contexts,
+            ciphertexts,
+            aads,
+            decryption_shares,
+            shared_secrets,
+        } = setup_block(2);
+        let aggregated = aggregate_decryption_shares(
+            &ciphertexts,
+            &aads,
+            &decryption_shares,
+            &shared_secrets,
+        )
+        .unwrap();
+        let pub_contexts = &contexts[0].public_decryption_contexts;
+
+        assert!(!verify_aggregated_decryption_shares(
+            &[],
+            &ciphertexts,
+            &aads,
+            &shared_secrets,
+            &aggregated,
+        ));
+        assert!(!verify_aggregated_combination(
+            &[],
+            &ciphertexts,
+            &aads,
+            &shared_secrets,
+            &aggregated,
+        ));
+        assert!(!verify_aggregated_combination(
+            &pub_contexts[..1],
+            &ciphertexts,
+            &aads,
+            &shared_secrets,
+            &aggregated,
+        ));
+    }
+}
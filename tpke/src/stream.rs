@@ -0,0 +1,279 @@
+use crate::*;
+
+use std::io::{ErrorKind, Read, Write};
+
+/// Plaintext size of every chunk of a stream but the last, which is always shorter
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+const STREAM_NONCE_DOMAIN: &[u8] = b"FERVEO_STREAM_NONCE";
+
+/// Threshold-encrypts everything read from `reader`, writing the encrypted chunks to `writer`
+///
+/// Returns the header (U, W) of the stream, a [`Ciphertext`] with an empty payload V.
+/// The header is validated and decrypted like any other ciphertext, and its symmetric key
+/// decrypts the chunks. Each chunk is sealed under a nonce made of its index and a flag
+/// marking the last chunk, so that reordered, dropped or truncated chunks fail to decrypt.
+pub fn encrypt_stream<R, W, Rn, E>(
+    reader: &mut R,
+    writer: &mut W,
+    aad: &[u8],
+    pubkey: &E::G1Affine,
+    suite: CipherSuiteId,
+    mode: AeadMode,
+    rng: &mut Rn,
+) -> Result<Ciphertext<E>>
+where
+    R: Read,
+    W: Write,
+    Rn: RngCore,
+    E: HashToCurve,
+{
+    let (rand_element, commitment, symmetric_key) =
+        encapsulate_symmetric_key::<Rn, E>(pubkey, suite, rng);
+    let nonce_prefix = stream_nonce_prefix::<E>(commitment);
+
+    let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+    let mut counter = 0u32;
+    loop {
+        let len = read_chunk(reader, &mut chunk)?;
+        let is_last = len < STREAM_CHUNK_SIZE;
+        let nonce = chunk_nonce(&nonce_prefix, counter, is_last, suite);
+        writer.write_all(&suite.encrypt(
+            &symmetric_key,
+            &nonce,
+            &chunk[..len],
+        ))?;
+        if is_last {
+            break;
+        }
+        counter = next_counter(counter)?;
+    }
+
+    Ok(seal(
+        rand_element,
+        commitment,
+        vec![],
+        &symmetric_key,
+        suite,
+        mode,
+        aad,
+    ))
+}
+
+/// Decrypts the chunks read from `reader` with the symmetric key of the stream `header`
+///
+/// Plaintext is written to `writer` chunk by chunk as each chunk is authenticated, so on
+/// error everything written so far must be discarded.
+/// Does not check the header validity, see [`check_ciphertext_validity`].
+pub fn decrypt_stream_with_symmetric_key<R, W, E>(
+    header: &Ciphertext<E>,
+    symmetric_key: &[u8; 32],
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<()>
+where
+    R: Read,
+    W: Write,
+    E: HashToCurve,
+{
+    check_key_commitment(header, symmetric_key)?;
+    let suite = header.suite;
+    let nonce_prefix = stream_nonce_prefix::<E>(header.commitment);
+
+    let chunk_len = STREAM_CHUNK_SIZE + suite.tag_len();
+    let mut chunk = vec![0u8; chunk_len];
+    let mut counter = 0u32;
+    loop {
+        let len = read_chunk(reader, &mut chunk)?;
+        let is_last = len < chunk_len;
+        let nonce = chunk_nonce(&nonce_prefix, counter, is_last, suite);
+        writer.write_all(&suite.decrypt(
+            symmetric_key,
+            &nonce,
+            &chunk[..len],
+        )?)?;
+        if is_last {
+            return Ok(());
+        }
+        counter = next_counter(counter)?;
+    }
+}
+
+pub fn checked_decrypt_stream<R, W, E>(
+    header: &Ciphertext<E>,
+    aad: &[u8],
+    g_inv: &E::G1Prepared,
+    privkey: &E::G2Affine,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<()>
+where
+    R: Read,
+    W: Write,
+    E: HashToCurve,
+{
+    let s = E::product_of_pairings(&[(
+        E::G1Prepared::from(header.commitment),
+        E::G2Prepared::from(*privkey),
+    )]);
+    checked_decrypt_stream_with_shared_secret(
+        header, aad, g_inv, &s, reader, writer,
+    )
+}
+
+pub fn checked_decrypt_stream_with_shared_secret<R, W, E>(
+    header: &Ciphertext<E>,
+    aad: &[u8],
+    g_inv: &E::G1Prepared,
+    shared_secret: &E::Fqk,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<()>
+where
+    R: Read,
+    W: Write,
+    E: HashToCurve,
+{
+    check_ciphertext_validity(header, aad, g_inv)?;
+    decrypt_stream_with_symmetric_key(
+        header,
+        &shared_secret_to_symmetric_key::<E>(shared_secret, header.suite),
+        reader,
+        writer,
+    )
+}
+
+fn stream_nonce_prefix<E: PairingEngine>(commitment: E::G1Affine) -> Vec<u8> {
+    sha256(
+        &[
+            STREAM_NONCE_DOMAIN,
+            &nonce_seed_from_commitment::<E>(commitment),
+        ]
+        .concat(),
+    )
+}
+
+/// Nonce prefix, followed by the big-endian chunk index and the last chunk flag
+fn chunk_nonce(
+    nonce_prefix: &[u8],
+    counter: u32,
+    is_last: bool,
+    suite: CipherSuiteId,
+) -> Vec<u8> {
+    let mut nonce = nonce_prefix[..suite.nonce_len() - 5].to_vec();
+    nonce.extend_from_slice(&counter.to_be_bytes());
+    nonce.push(is_last as u8);
+    nonce
+}
+
+fn next_counter(counter: u32) -> Result<u32> {
+    counter.checked_add(1).ok_or_else(|| {
+        std::io::Error::new(ErrorKind::InvalidData, "stream is too long").into()
+    })
+}
+
+/// Fills `chunk` from `reader`, returning fewer bytes only at the end of the stream
+fn read_chunk<R: Read>(reader: &mut R, chunk: &mut [u8]) -> Result<usize> {
+    let mut len = 0;
+    while len < chunk.len() {
+        match reader.read(&mut chunk[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use ark_std::test_rng;
+    use rand::RngCore;
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[test]
+    fn stream_roundtrip() {
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let (pubkey, privkey, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        for len in [0, 100, STREAM_CHUNK_SIZE, STREAM_CHUNK_SIZE * 5 / 2] {
+            let mut msg = vec![0u8; len];
+            rng.fill_bytes(&mut msg);
+
+            let mut encrypted = vec![];
+            let header = encrypt_stream::<_, _, _, E>(
+                &mut &msg[..],
+                &mut encrypted,
+                aad,
+                &pubkey,
+                CipherSuiteId::default(),
+                AeadMode::KeyCommitting,
+                rng,
+            )
+            .unwrap();
+
+            let mut decrypted = vec![];
+            checked_decrypt_stream(
+                &header,
+                aad,
+                g_inv,
+                &privkey,
+                &mut &encrypted[..],
+                &mut decrypted,
+            )
+            .unwrap();
+            assert_eq!(decrypted, msg);
+        }
+    }
+
+    #[test]
+    fn stream_rejects_reordering_and_truncation() {
+        let rng = &mut test_rng();
+        let aad: &[u8] = "my-aad".as_bytes();
+        let (pubkey, privkey, contexts) = setup_fast::<E>(2, 3, rng);
+        let g_inv = &contexts[0].setup_params.g_inv;
+
+        let msg = vec![7u8; STREAM_CHUNK_SIZE * 2];
+        let mut encrypted = vec![];
+        let header = encrypt_stream::<_, _, _, E>(
+            &mut &msg[..],
+            &mut encrypted,
+            aad,
+            &pubkey,
+            CipherSuiteId::Aes256GcmHkdfSha256,
+            AeadMode::Standard,
+            rng,
+        )
+        .unwrap();
+        let chunk_len = STREAM_CHUNK_SIZE + header.suite.tag_len();
+        // Two full chunks followed by an empty last chunk
+        assert_eq!(encrypted.len(), 2 * chunk_len + header.suite.tag_len());
+
+        let decrypt = |encrypted: &[u8]| {
+            checked_decrypt_stream(
+                &header,
+                aad,
+                g_inv,
+                &privkey,
+                &mut &encrypted[..],
+                &mut vec![],
+            )
+        };
+        assert!(decrypt(&encrypted).is_ok());
+
+        let mut reordered = encrypted[chunk_len..2 * chunk_len].to_vec();
+        reordered.extend_from_slice(&encrypted[..chunk_len]);
+        reordered.extend_from_slice(&encrypted[2 * chunk_len..]);
+        assert!(decrypt(&reordered).is_err());
+
+        // Dropping the last chunk, at a chunk boundary
+        assert!(decrypt(&encrypted[..2 * chunk_len]).is_err());
+        assert!(decrypt(&encrypted[..chunk_len]).is_err());
+        assert!(decrypt(&encrypted[..encrypted.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+//! Conformance of [`HashToCurve`] with the test vectors of RFC 9380, appendix J
+//! See: https://www.rfc-editor.org/rfc/rfc9380.html#appendix-J
+
+use ark_bls12_381::{Bls12_381, Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use group_threshold_cryptography::HashToCurve;
+
+const G1_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+const G2_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+fn fq_from_hex(hex_string: &str) -> Fq {
+    Fq::from_be_bytes_mod_order(&hex::decode(hex_string).unwrap())
+}
+
+/// Decodes a point in the compressed format of the ZCash BLS12-381 serialization
+fn g2_from_compressed(hex_string: &str) -> G2Affine {
+    let mut compressed = [0u8; 96];
+    hex::decode_to_slice(hex_string, &mut compressed)
+        .expect("Failed to decode hex");
+    let greatest = compressed[0] & (1 << 5) != 0;
+    compressed[0] &= (1 << 5) - 1;
+
+    let coordinate = |bytes: &[u8]| {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Fq::deserialize(&bytes[..]).unwrap()
+    };
+    let x =
+        Fq2::new(coordinate(&compressed[48..]), coordinate(&compressed[..48]));
+    G2Affine::get_point_from_x(x, greatest).unwrap()
+}
+
+fn test_hash_to_g2(msg: &[u8], expected_hex_string: &str) {
+    let expected = g2_from_compressed(expected_hex_string);
+    let res = Bls12_381::hash_to_g2(msg, G2_DST);
+    assert!(res == expected)
+}
+
+#[test]
+fn hash_nothing_g2() {
+    let msg = b"";
+    let expected_hex_string =
+        "a5cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a";
+    test_hash_to_g2(msg, expected_hex_string)
+}
+
+#[test]
+fn hash_abc_g2() {
+    let msg = b"abc";
+    let expected_hex_string =
+        "939cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd802c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6";
+    test_hash_to_g2(msg, expected_hex_string)
+}
+
+#[test]
+fn hash_nothing_g1() {
+    let expected = G1Affine::new(
+        fq_from_hex("052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1"),
+        fq_from_hex("08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265"),
+        false,
+    );
+    assert_eq!(Bls12_381::hash_to_g1(b"", G1_DST), expected);
+}